@@ -0,0 +1,54 @@
+//! Counts heap allocations made while solving the hard puzzle sets.
+//!
+//! Run with `cargo bench --bench allocations`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+use sudoku_solver::{Sudoku, SudokuBoard};
+
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn main() {
+    let board = SudokuBoard::new();
+    for path in ["benches/data/top95.txt", "benches/data/hardest.txt"] {
+        let puzzles: Vec<Sudoku> = fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|l| Sudoku::from(l, &board).unwrap())
+            .collect();
+
+        let before = ALLOCATIONS.load(Ordering::Relaxed);
+        let start = Instant::now();
+        for p in &puzzles {
+            assert!(p.solve().is_some());
+        }
+        let elapsed = start.elapsed();
+        let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+        println!(
+            "{}: {} puzzles, {} allocations ({} per puzzle), {:?}",
+            path,
+            puzzles.len(),
+            allocations,
+            allocations / puzzles.len(),
+            elapsed
+        );
+    }
+}
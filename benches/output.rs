@@ -0,0 +1,53 @@
+//! How fast solutions can be turned into text, by [`Display`] and by
+//! [`Sudoku::write_line`].
+//!
+//! Run with `cargo bench --bench output`. Criterion reports the time to
+//! write every solution of the `top95` set into one buffer, and with it
+//! the solutions written per second.
+//!
+//! [`Display`]: std::fmt::Display
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use std::fmt::Write;
+use std::fs;
+use sudoku_solver::{Sudoku, SudokuBoard};
+
+fn by_format(c: &mut Criterion) {
+    let board = SudokuBoard::standard();
+    let solutions: Vec<Sudoku> = fs::read_to_string("benches/data/top95.txt")
+        .unwrap()
+        .lines()
+        .map(|l| Sudoku::from(l, board).unwrap().solve().unwrap())
+        .collect();
+    let mut out = String::new();
+    let mut group = c.benchmark_group("output");
+    group.throughput(Throughput::Elements(solutions.len() as u64));
+    group.bench_function("display", |b| {
+        b.iter(|| {
+            out.clear();
+            for s in &solutions {
+                write!(out, "{}", s).unwrap();
+            }
+        })
+    });
+    group.bench_function("to_line", |b| {
+        b.iter(|| {
+            out.clear();
+            for s in &solutions {
+                out.push_str(&s.to_line());
+            }
+        })
+    });
+    group.bench_function("write_line", |b| {
+        b.iter(|| {
+            out.clear();
+            for s in &solutions {
+                s.write_line(&mut out).unwrap();
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, by_format);
+criterion_main!(benches);
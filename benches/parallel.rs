@@ -0,0 +1,44 @@
+//! Compares solving a large batch of puzzles one by one against
+//! [`solve_all`] spreading them over threads.
+//!
+//! Run with `cargo bench --bench parallel --features parallel`.
+
+use std::fs;
+use std::time::Instant;
+use sudoku_solver::{solve_all, Sudoku, SudokuBoard};
+
+/// How many times the hard puzzle sets are repeated to make the batch.
+const COPIES: usize = 20;
+
+fn main() {
+    let board = SudokuBoard::new();
+    let mut lines = Vec::new();
+    for path in ["benches/data/top95.txt", "benches/data/hardest.txt"] {
+        let text = fs::read_to_string(path).unwrap();
+        lines.extend(text.lines().map(String::from));
+    }
+    let lines: Vec<String> = lines
+        .iter()
+        .cycle()
+        .take(lines.len() * COPIES)
+        .cloned()
+        .collect();
+
+    let start = Instant::now();
+    for l in &lines {
+        assert!(Sudoku::from(l, &board).unwrap().solve().is_some());
+    }
+    let sequential = start.elapsed();
+
+    let start = Instant::now();
+    assert!(solve_all(&lines, &board).iter().all(|r| r.is_ok()));
+    let parallel = start.elapsed();
+
+    println!(
+        "{} puzzles: sequential {:?}, parallel {:?} ({:.1}x)",
+        lines.len(),
+        sequential,
+        parallel,
+        sequential.as_secs_f64() / parallel.as_secs_f64()
+    );
+}
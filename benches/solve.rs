@@ -0,0 +1,99 @@
+//! Solve times over the puzzle sets in `benches/data`, per set and per
+//! difficulty band.
+//!
+//! Run with `cargo bench --bench solve`. Criterion reports the time to
+//! solve a whole set, and with it the time per puzzle. The `order` group
+//! compares the [`ValueOrder`]s of the search on the hardest set.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::fs;
+use sudoku_solver::{Difficulty, Sudoku, SudokuBoard, ValueOrder};
+
+const SETS: [(&str, &str); 3] = [
+    ("top95", "benches/data/top95.txt"),
+    ("hardest", "benches/data/hardest.txt"),
+    ("seventeen", "benches/data/seventeen.txt"),
+];
+
+const BANDS: [Difficulty; 5] = [
+    Difficulty::Trivial,
+    Difficulty::Easy,
+    Difficulty::Medium,
+    Difficulty::Hard,
+    Difficulty::Diabolical,
+];
+
+fn load<'a>(path: &str, board: &'a SudokuBoard) -> Vec<Sudoku<'a>> {
+    fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(|l| Sudoku::from(l, board).unwrap())
+        .collect()
+}
+
+fn solve_all(puzzles: &[Sudoku]) {
+    for p in puzzles {
+        assert!(p.solve().is_some());
+    }
+}
+
+fn by_set(c: &mut Criterion) {
+    let board = SudokuBoard::standard();
+    let mut group = c.benchmark_group("set");
+    for (name, path) in SETS {
+        let puzzles = load(path, board);
+        group.throughput(Throughput::Elements(puzzles.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), &puzzles, |b, p| {
+            b.iter(|| solve_all(p))
+        });
+    }
+    group.finish();
+}
+
+fn by_difficulty(c: &mut Criterion) {
+    let board = SudokuBoard::standard();
+    let puzzles: Vec<Sudoku> = SETS
+        .iter()
+        .flat_map(|(_, path)| load(path, board))
+        .collect();
+    let mut group = c.benchmark_group("difficulty");
+    for band in BANDS {
+        let rated: Vec<Sudoku> = puzzles
+            .iter()
+            .filter(|p| p.difficulty() == band)
+            .cloned()
+            .collect();
+        if rated.is_empty() {
+            continue;
+        }
+        group.throughput(Throughput::Elements(rated.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{:?}", band)),
+            &rated,
+            |b, p| b.iter(|| solve_all(p)),
+        );
+    }
+    group.finish();
+}
+
+fn by_value_order(c: &mut Criterion) {
+    let board = SudokuBoard::standard();
+    let puzzles = load("benches/data/hardest.txt", board);
+    let mut group = c.benchmark_group("order");
+    group.throughput(Throughput::Elements(puzzles.len() as u64));
+    for order in [
+        ValueOrder::Ascending,
+        ValueOrder::Random(1),
+        ValueOrder::LeastConstraining,
+    ] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{:?}", order)),
+            &puzzles,
+            |b, p| b.iter(|| p.iter().all(|p| p.solve_with(order).is_some())),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, by_set, by_difficulty, by_value_order);
+criterion_main!(benches);
@@ -0,0 +1,164 @@
+use crate::board::SudokuBoard;
+use crate::error::SolveError;
+use crate::sudoku::Sudoku;
+use std::io::{self, BufRead, Write};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Solves every line of `lines` on `board`, one result per line in the
+/// order of the input.
+///
+/// The puzzles are independent and only share the board, so with the
+/// `parallel` feature on they are solved across rayon's thread pool.
+/// Without it they are solved one after the other.
+pub fn solve_all<'a>(
+    lines: &[String],
+    board: &'a SudokuBoard,
+) -> Vec<Result<Sudoku<'a>, SolveError>> {
+    #[cfg(feature = "parallel")]
+    let lines = lines.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let lines = lines.iter();
+    lines.map(|s| solve_one(s, board)).collect()
+}
+
+/// Solves the puzzles read from `reader`, one per line, on `board`, writing
+/// each solution to `writer` as it's found, formatted like
+/// [`Display`](std::fmt::Display) does, by the time the next line is read.
+///
+/// Blank lines are skipped. A line that is malformed or has no solution
+/// gets `line N: error` written in place of a solution, `N` counting from
+/// 1, and the lines after it are solved all the same. Only failing to read
+/// or write stops the stream, the error saying on which line it happened.
+pub fn solve_stream(
+    reader: impl BufRead,
+    writer: impl Write,
+    board: &SudokuBoard,
+) -> io::Result<()> {
+    solve_stream_with_progress(reader, writer, board, usize::MAX, |_| {})
+}
+
+/// Like [`solve_stream`], calling `progress` with the number of puzzles
+/// done so far after every `every` of them, and once more after the last
+/// one unless that was just reported. Malformed and unsolvable puzzles
+/// count as done, blank lines don't.
+///
+/// # Panics
+///
+/// If `every` is 0.
+pub fn solve_stream_with_progress(
+    reader: impl BufRead,
+    mut writer: impl Write,
+    board: &SudokuBoard,
+    every: usize,
+    mut progress: impl FnMut(usize),
+) -> io::Result<()> {
+    assert!(
+        every > 0,
+        "progress must be reported every 1 puzzle or more"
+    );
+    let mut done: usize = 0;
+    for (n, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| io::Error::new(e.kind(), format!("line {}: {}", n + 1, e)))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match solve_one(&line, board) {
+            Ok(solved) => writeln!(writer, "{}", solved)?,
+            Err(e) => writeln!(writer, "line {}: {}", n + 1, e)?,
+        }
+        done += 1;
+        if done.is_multiple_of(every) {
+            progress(done);
+        }
+    }
+    if !done.is_multiple_of(every) {
+        progress(done);
+    }
+    writer.flush()
+}
+
+fn solve_one<'a>(s: &str, board: &'a SudokuBoard) -> Result<Sudoku<'a>, SolveError> {
+    Sudoku::from(s, board)?.try_solve()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{solve_all, solve_stream, solve_stream_with_progress};
+    use crate::board::SudokuBoard;
+    use crate::error::{ParseError, SolveError};
+    use crate::sudoku::Sudoku;
+    use std::fs;
+    use std::io::Cursor;
+
+    #[test]
+    fn results_follow_input_order() {
+        let board = SudokuBoard::new();
+        let mut lines: Vec<String> = fs::read_to_string("benches/data/top95.txt")
+            .unwrap()
+            .lines()
+            .map(String::from)
+            .collect();
+        lines.insert(10, "1234".to_string());
+
+        let results = solve_all(&lines, &board);
+        assert_eq!(results.len(), lines.len());
+        assert_eq!(
+            results[10].as_ref().err(),
+            Some(&SolveError::InvalidInput(ParseError::BadLength {
+                expected: 81,
+                got: 4
+            }))
+        );
+        for (line, result) in lines.iter().zip(&results).filter(|(_, r)| r.is_ok()) {
+            let solved = result.as_ref().unwrap().to_line();
+            let givens = line.chars().zip(solved.chars());
+            assert!(givens.filter(|(g, _)| *g != '.').all(|(g, s)| g == s));
+        }
+    }
+
+    #[test]
+    fn stream_writes_solutions_and_errors_in_order() {
+        let board = SudokuBoard::new();
+        let puzzle =
+            "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+        let input = format!("{}\n\n1234\n   \n{}", puzzle, puzzle);
+        let mut output = Vec::new();
+        solve_stream(Cursor::new(input), &mut output, &board).unwrap();
+
+        let solved = Sudoku::from(puzzle, &board).unwrap().solve().unwrap();
+        let expected = format!(
+            "{}\nline 3: invalid input: expected 81 cells, got 4\n{}\n",
+            solved, solved
+        );
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+
+        let mut output = Vec::new();
+        let bad = Cursor::new(vec![b'.', 0xff, b'\n']);
+        let e = solve_stream(bad, &mut output, &board).unwrap_err();
+        assert!(e.to_string().starts_with("line 1: "));
+    }
+
+    #[test]
+    fn progress_counts_puzzles_done() {
+        let board = SudokuBoard::new();
+        let top95 = fs::read_to_string("benches/data/top95.txt").unwrap();
+        let input = format!("\n1234\n{}", top95);
+        let mut reports = Vec::new();
+        let mut output = Vec::new();
+        solve_stream_with_progress(Cursor::new(&input), &mut output, &board, 40, |n| {
+            reports.push(n)
+        })
+        .unwrap();
+        assert_eq!(reports, vec![40, 80, 96]);
+
+        let mut reports = Vec::new();
+        let input = top95.lines().take(32).collect::<Vec<_>>().join("\n");
+        solve_stream_with_progress(Cursor::new(input), &mut output, &board, 16, |n| {
+            reports.push(n)
+        })
+        .unwrap();
+        assert_eq!(reports, vec![16, 32]);
+    }
+}
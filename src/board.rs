@@ -0,0 +1,515 @@
+use crate::error::RegionError;
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+/// The static structure of a Sudoku: its units (rows, columns and boxes),
+/// the units each cell belongs to, and each cell's peers.
+///
+/// Units are numbered rows first, then columns, then boxes, each group in
+/// reading order. A board holds no digits, so a single instance can be
+/// shared by any number of [`Sudoku`](crate::Sudoku) grids.
+pub struct SudokuBoard {
+    box_size: usize,
+    units: Vec<Vec<usize>>,
+    units_for: Vec<Vec<usize>>,
+    neighbors: Vec<HashSet<usize>>,
+}
+
+impl SudokuBoard {
+    /// The standard 9x9 board.
+    pub fn new() -> SudokuBoard {
+        SudokuBoard::with_box_size(3)
+    }
+
+    /// A board made of `n` by `n` boxes of `n` by `n` cells, holding digits
+    /// `1..=n*n`: 2 gives a 4x4 grid, 4 a 16x16 one.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `n` is between 1 and 4.
+    pub fn with_box_size(n: usize) -> SudokuBoard {
+        assert!((1..=4).contains(&n), "box size must be between 1 and 4");
+        SudokuBoard::from_units(n, SudokuBoard::standard_units(n))
+    }
+
+    /// The standard 9x9 board plus its two main diagonals as extra units,
+    /// for X-Sudoku.
+    pub fn new_diagonal() -> SudokuBoard {
+        SudokuBoard::from_extra_units(vec![
+            (0..9).map(|i| i * 9 + i).collect(),
+            (0..9).map(|i| i * 9 + 8 - i).collect(),
+        ])
+        .expect("the diagonals are units")
+    }
+
+    /// The standard 9x9 board plus `extra` units, numbered from 27 in the
+    /// order given, for variants whose rules add groups of cells that must
+    /// hold every digit once. A cell's peers take in the cells sharing any
+    /// unit with it, extra ones included.
+    ///
+    /// Fails unless every extra unit holds 9 distinct cells of the board.
+    /// An extra unit may repeat a row, column or box, which does no harm.
+    pub fn from_extra_units(extra: Vec<Vec<usize>>) -> Result<SudokuBoard, RegionError> {
+        for (u, unit) in extra.iter().enumerate() {
+            if let Some(&cell) = unit.iter().find(|c| **c >= 81) {
+                return Err(RegionError::OutOfRange { cell });
+            }
+            let distinct: HashSet<&usize> = unit.iter().collect();
+            if unit.len() != 9 || distinct.len() != 9 {
+                return Err(RegionError::BadUnit { unit: u });
+            }
+        }
+        let mut units = SudokuBoard::standard_units(3);
+        units.extend(extra);
+        Ok(SudokuBoard::from_units(3, units))
+    }
+
+    /// A 9x9 board for jigsaw sudoku, with `regions` taking the place of
+    /// the boxes: units 18 to 26 are the regions in the order given, while
+    /// rows and columns stay as they are.
+    ///
+    /// Fails unless the regions split the 81 cells between them, each cell
+    /// in exactly one region.
+    pub fn with_regions(regions: [[usize; 9]; 9]) -> Result<SudokuBoard, RegionError> {
+        let mut seen = [false; 81];
+        for &cell in regions.iter().flatten() {
+            if cell >= seen.len() {
+                return Err(RegionError::OutOfRange { cell });
+            }
+            if seen[cell] {
+                return Err(RegionError::Repeated { cell });
+            }
+            seen[cell] = true;
+        }
+        let mut units = SudokuBoard::standard_units(3);
+        units.truncate(18);
+        units.extend(regions.iter().map(|r| r.to_vec()));
+        Ok(SudokuBoard::from_units(3, units))
+    }
+
+    /// The standard 9x9 board plus the four windows of windoku as extra
+    /// units: the 3x3 squares one cell in from each corner of the grid.
+    pub fn new_windoku() -> SudokuBoard {
+        let windows = [(1, 1), (1, 5), (5, 1), (5, 5)]
+            .iter()
+            .map(|(top, left)| (0..9).map(|i| (top + i / 3) * 9 + left + i % 3).collect())
+            .collect();
+        SudokuBoard::from_extra_units(windows).expect("the windows are units")
+    }
+
+    /// The standard 9x9 board for anti-knight sudoku, where cells a chess
+    /// knight's move apart can't hold the same digit either.
+    ///
+    /// Knight moves make peers but no units, so the rule reaches the
+    /// candidates of the cells around each digit placed, not the hidden
+    /// singles: [`neighbors`](SudokuBoard::neighbors) gains the knight
+    /// cells, while [`units_for`](SudokuBoard::units_for) stays standard.
+    pub fn new_anti_knight() -> SudokuBoard {
+        let mut board = SudokuBoard::new();
+        for k in 0..81 {
+            let (r, c) = (board.row_of(k) as isize, board.col_of(k) as isize);
+            for (dr, dc) in [
+                (-2, -1),
+                (-2, 1),
+                (-1, -2),
+                (-1, 2),
+                (1, -2),
+                (1, 2),
+                (2, -1),
+                (2, 1),
+            ] {
+                let (r, c) = (r + dr, c + dc);
+                if (0..9).contains(&r) && (0..9).contains(&c) {
+                    let peer = board.cell_index(r as usize, c as usize);
+                    board.neighbors[k].insert(peer);
+                }
+            }
+        }
+        board
+    }
+
+    /// Rows, columns and boxes of a board with boxes of size `n`.
+    fn standard_units(n: usize) -> Vec<Vec<usize>> {
+        let side = n * n;
+        let mut units: Vec<Vec<usize>> = vec![Vec::new(); 3 * side];
+        for i in 0..side {
+            for j in 0..side {
+                let p = i * side + j;
+                for u in [i, side + j, 2 * side + (i / n) * n + j / n] {
+                    units[u].push(p);
+                }
+            }
+        }
+        units
+    }
+
+    /// A board with the given units, working out which units each cell
+    /// belongs to and which cells are its peers.
+    fn from_units(box_size: usize, units: Vec<Vec<usize>>) -> SudokuBoard {
+        let cells = box_size.pow(4);
+        let mut units_for: Vec<Vec<usize>> = vec![Vec::new(); cells];
+        let mut neighbors: Vec<HashSet<usize>> = vec![HashSet::new(); cells];
+
+        for (u, unit) in units.iter().enumerate() {
+            for &p in unit {
+                units_for[p].push(u);
+            }
+        }
+        for k in 0..neighbors.len() {
+            for u in &units_for[k] {
+                for &v in &units[*u] {
+                    if v != k {
+                        neighbors[k].insert(v);
+                    }
+                }
+            }
+        }
+
+        SudokuBoard {
+            box_size,
+            units,
+            units_for,
+            neighbors,
+        }
+    }
+
+    /// The width and height of a box.
+    #[inline]
+    pub fn box_size(&self) -> usize {
+        self.box_size
+    }
+
+    /// The width and height of the grid, which is also the largest digit.
+    #[inline]
+    pub fn side(&self) -> usize {
+        self.box_size * self.box_size
+    }
+
+    /// The number of cells in the grid.
+    #[inline]
+    pub fn cell_count(&self) -> usize {
+        self.units_for.len()
+    }
+
+    /// The number of units.
+    #[inline]
+    pub fn unit_count(&self) -> usize {
+        self.units.len()
+    }
+
+    /// The row of `cell`, counting from 0 at the top.
+    #[inline]
+    pub fn row_of(&self, cell: usize) -> usize {
+        cell / self.side()
+    }
+
+    /// The column of `cell`, counting from 0 at the left.
+    #[inline]
+    pub fn col_of(&self, cell: usize) -> usize {
+        cell % self.side()
+    }
+
+    /// The box of `cell`, counting from 0 in reading order, so that its
+    /// unit is `2 * side + box_of(cell)`. Boxes are the square ones even on
+    /// a jigsaw board, whose regions replace them as units.
+    #[inline]
+    pub fn box_of(&self, cell: usize) -> usize {
+        let n = self.box_size;
+        (self.row_of(cell) / n) * n + self.col_of(cell) / n
+    }
+
+    /// The cell at `row` and `col`, the inverse of
+    /// [`row_of`](SudokuBoard::row_of) and [`col_of`](SudokuBoard::col_of).
+    #[inline]
+    pub fn cell_index(&self, row: usize, col: usize) -> usize {
+        row * self.side() + col
+    }
+
+    /// The standard 9x9 board, built on first use and shared thereafter by
+    /// every caller on every thread.
+    ///
+    /// Use [`SudokuBoard::new`] instead for a board of your own.
+    pub fn standard() -> &'static SudokuBoard {
+        static STANDARD: OnceLock<SudokuBoard> = OnceLock::new();
+        STANDARD.get_or_init(SudokuBoard::new)
+    }
+
+    /// The cells of `unit`, in reading order for rows, columns and boxes
+    /// and in the order given for any other unit.
+    #[inline]
+    pub fn cells_in_unit(&self, unit: usize) -> &[usize] {
+        &self.units[unit]
+    }
+
+    /// The units `cell` belongs to, in ascending order.
+    #[inline]
+    pub fn units_for(&self, cell: usize) -> &[usize] {
+        &self.units_for[cell]
+    }
+
+    /// The peers of `cell`: every other cell that can't hold the same
+    /// digit. That's every cell sharing a unit with it, 20 on the standard
+    /// board, plus the knight moves on an
+    /// [anti-knight](SudokuBoard::new_anti_knight) board.
+    #[inline]
+    pub fn neighbors(&self, cell: usize) -> &HashSet<usize> {
+        &self.neighbors[cell]
+    }
+}
+
+impl Default for SudokuBoard {
+    fn default() -> Self {
+        SudokuBoard::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SudokuBoard;
+    use crate::error::RegionError;
+    use crate::grid::Grid;
+    use crate::sudoku::Sudoku;
+    use std::collections::HashSet;
+    use std::thread;
+
+    #[test]
+    #[allow(clippy::zero_prefixed_literal)]
+    fn init_works() {
+        let s = SudokuBoard::new();
+        assert_eq!(s.units.len(), 27);
+        assert!((0..81).map(|i| s.units_for[i].len()).all(|n| n == 3));
+        assert!((0..81).map(|i| s.neighbors[i].len()).all(|n| n == 20));
+        assert_eq!(s.units_for[19], vec![2, 10, 18]);
+        assert_eq!(s.units[18], vec![0, 1, 2, 9, 10, 11, 18, 19, 20]);
+        assert_eq!(
+            s.neighbors[19],
+            HashSet::from_iter(vec![
+                18, 20, 21, 22, 23, 24, 25, 26, 01, 10, 28, 37, 46, 55, 64, 73, 00, 02, 09, 11,
+            ])
+        );
+    }
+
+    #[test]
+    fn accessors_agree() {
+        let s = SudokuBoard::new_windoku();
+        for u in 0..s.unit_count() {
+            assert!(s
+                .cells_in_unit(u)
+                .iter()
+                .all(|k| s.units_for(*k).contains(&u)));
+        }
+        for k in 0..s.cell_count() {
+            let peers: HashSet<usize> = s
+                .units_for(k)
+                .iter()
+                .flat_map(|u| s.cells_in_unit(*u))
+                .copied()
+                .filter(|p| *p != k)
+                .collect();
+            assert_eq!(s.neighbors(k), &peers);
+        }
+    }
+
+    #[test]
+    fn other_box_sizes() {
+        let s = SudokuBoard::with_box_size(2);
+        assert_eq!((s.side(), s.cell_count(), s.units.len()), (4, 16, 12));
+        assert!((0..16).all(|i| s.neighbors[i].len() == 7));
+        assert_eq!(s.units[8], vec![0, 1, 4, 5]);
+        assert_eq!(
+            s.neighbors[5],
+            HashSet::from_iter(vec![4, 6, 7, 1, 9, 13, 0])
+        );
+
+        let s = SudokuBoard::with_box_size(4);
+        assert_eq!((s.side(), s.cell_count(), s.units.len()), (16, 256, 48));
+        assert!((0..256).all(|i| s.neighbors[i].len() == 39));
+    }
+
+    #[test]
+    fn cell_coordinates() {
+        let s = SudokuBoard::new();
+        assert_eq!((s.row_of(19), s.col_of(19), s.box_of(19)), (2, 1, 0));
+        assert_eq!((s.row_of(80), s.col_of(80), s.box_of(80)), (8, 8, 8));
+        assert_eq!((s.row_of(33), s.col_of(33), s.box_of(33)), (3, 6, 5));
+        for n in 1..=4 {
+            let s = SudokuBoard::with_box_size(n);
+            let side = s.side();
+            for k in 0..s.cell_count() {
+                let (r, c) = (s.row_of(k), s.col_of(k));
+                assert_eq!(s.cell_index(r, c), k);
+                assert_eq!(
+                    s.units_for(k),
+                    [r, side + c, 2 * side + s.box_of(k)].as_slice()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn standard_is_shared_across_threads() {
+        let boards: Vec<usize> = (0..8)
+            .map(|_| thread::spawn(|| SudokuBoard::standard() as *const _ as usize))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|t| t.join().unwrap())
+            .collect();
+        let here = SudokuBoard::standard() as *const _ as usize;
+        assert!(boards.iter().all(|b| *b == here));
+        assert_eq!(SudokuBoard::standard().unit_count(), 27);
+    }
+
+    #[test]
+    fn jigsaw_board() {
+        let mut regions = [[0; 9]; 9];
+        for (r, region) in regions.iter_mut().enumerate() {
+            for (i, cell) in region.iter_mut().enumerate() {
+                *cell = (r / 3 * 3 + i / 3) * 9 + r % 3 * 3 + i % 3;
+            }
+        }
+        // Trade a corner cell between the two leftmost middle boxes.
+        regions[3][2] = 39;
+        regions[4][3] = 29;
+        let board = SudokuBoard::with_regions(regions).unwrap();
+        assert_eq!(board.unit_count(), 27);
+        assert_eq!(board.cells_in_unit(21), &regions[3]);
+        assert!(board.neighbors(29).contains(&41));
+        assert!(!board.neighbors(29).contains(&37));
+
+        let puzzle =
+            "6..27...9..........75.....3.8.7.62....7...94.....8.7...51...39.9.......47...19...";
+        let sudoku = Sudoku::from(puzzle, &board).unwrap();
+        assert!(sudoku.has_unique_solution());
+        let solved = Grid::from(&sudoku.solve().unwrap());
+        assert_eq!(solved.validate(&board), Ok(()));
+        // Columns 0 to 2 and regions 0, 3 and 6 cover the same cells bar the
+        // traded ones, so those must hold the same digit.
+        assert_eq!(solved.digits()[29], solved.digits()[39]);
+
+        let mut bad = regions;
+        bad[0][0] = 81;
+        assert_eq!(
+            SudokuBoard::with_regions(bad).err(),
+            Some(RegionError::OutOfRange { cell: 81 })
+        );
+        bad[0][0] = 1;
+        assert_eq!(
+            SudokuBoard::with_regions(bad).err(),
+            Some(RegionError::Repeated { cell: 1 })
+        );
+    }
+
+    #[test]
+    fn windoku_board() {
+        let board = SudokuBoard::new_windoku();
+        assert_eq!(board.unit_count(), 31);
+        assert_eq!(
+            board.cells_in_unit(27),
+            &[10, 11, 12, 19, 20, 21, 28, 29, 30]
+        );
+        // Cell 20 shares the first window with three cells beyond its row,
+        // column and box.
+        assert_eq!(board.neighbors(20).len(), 20 + 3);
+        assert_eq!(board.neighbors(0).len(), 20);
+
+        let puzzle =
+            ".7.9..1.3.....28..9............6....7.6......84....6.53..........2............3..";
+        assert!(!Sudoku::from(puzzle, &SudokuBoard::new())
+            .unwrap()
+            .has_unique_solution());
+        let sudoku = Sudoku::from(puzzle, &board).unwrap();
+        assert!(sudoku.has_unique_solution());
+        let solved = Grid::from(&sudoku.solve().unwrap());
+        assert_eq!(solved.validate(&board), Ok(()));
+    }
+
+    #[test]
+    fn extra_units() {
+        // A centre dot sudoku: the centre cells of the boxes are a unit.
+        let centres: Vec<usize> = (0..9)
+            .map(|b| (b / 3 * 3 + 1) * 9 + b % 3 * 3 + 1)
+            .collect();
+        let board = SudokuBoard::from_extra_units(vec![centres.clone()]).unwrap();
+        assert_eq!(board.unit_count(), 28);
+        assert_eq!(board.cells_in_unit(27), centres.as_slice());
+        assert_eq!(board.units_for(10), &[1, 10, 18, 27]);
+        // Of the other centres, two share a row with cell 10 and two a
+        // column, so four are new peers.
+        assert_eq!(board.neighbors(10).len(), 20 + 4);
+        let solved = Sudoku::empty(&board).solve().unwrap();
+        assert_eq!(Grid::from(&solved).validate(&board), Ok(()));
+
+        let mut bad = centres.clone();
+        bad[8] = 81;
+        assert_eq!(
+            SudokuBoard::from_extra_units(vec![centres.clone(), bad]).err(),
+            Some(RegionError::OutOfRange { cell: 81 })
+        );
+        let short = centres[..8].to_vec();
+        assert_eq!(
+            SudokuBoard::from_extra_units(vec![short]).err(),
+            Some(RegionError::BadUnit { unit: 0 })
+        );
+        let mut repeated = centres.clone();
+        repeated[8] = repeated[0];
+        assert_eq!(
+            SudokuBoard::from_extra_units(vec![centres, repeated]).err(),
+            Some(RegionError::BadUnit { unit: 1 })
+        );
+    }
+
+    #[test]
+    fn anti_knight_board() {
+        let board = SudokuBoard::new_anti_knight();
+        assert_eq!(board.unit_count(), 27);
+        assert_eq!(board.units_for(40), &[4, 13, 22]);
+        let knights = [21, 23, 29, 33, 47, 51, 57, 59];
+        assert_eq!(board.neighbors(40).len(), 20 + knights.len());
+        assert!(knights.iter().all(|k| board.neighbors(40).contains(k)));
+        assert!(knights.iter().all(|k| board.neighbors(*k).contains(&40)));
+        // The knight moves from a corner stay in its box.
+        assert_eq!(board.neighbors(0).len(), 20);
+
+        let puzzle =
+            "64.........5..9..7.......6.41..386........8...8.......1.4.2.3.89.3.....28..3....6";
+        assert!(!Sudoku::from(puzzle, &SudokuBoard::new())
+            .unwrap()
+            .has_unique_solution());
+        let sudoku = Sudoku::from(puzzle, &board).unwrap();
+        assert!(sudoku.has_unique_solution());
+        let solved = sudoku.solve().unwrap();
+        assert_eq!(
+            solved.to_line(),
+            "648273519235169487791584263419738625327615894586492731164927358973856142852341976"
+        );
+        assert!(solved.is_valid_solution());
+
+        // Swapping two rows of a band keeps a plain solution valid, but
+        // brings some knight moves onto equal digits.
+        let line = solved.to_line();
+        let swapped = format!("{}{}{}", &line[9..18], &line[..9], &line[18..]);
+        let plain = SudokuBoard::new();
+        assert!(Sudoku::from(&swapped, &plain).unwrap().is_valid_solution());
+        assert!(Sudoku::from(&swapped, &board).is_err());
+    }
+
+    #[test]
+    fn diagonal_board() {
+        let d = SudokuBoard::new_diagonal();
+        assert_eq!(d.units.len(), 29);
+        assert_eq!(d.units_for[0], vec![0, 9, 18, 27]);
+        assert_eq!(d.units_for[40], vec![4, 13, 22, 27, 28]);
+        assert_eq!(d.neighbors[0].len(), 26);
+        assert_eq!(d.neighbors[40].len(), 32);
+
+        // Ambiguous under the plain rules, unique once the diagonals count.
+        let s = "7.....9.22.9....8....2597..17.....95........8.3.9.....4.......7...8...2.....6..19";
+        let plain = SudokuBoard::new();
+        assert!(!Sudoku::from(s, &plain).unwrap().has_unique_solution());
+        let sudoku = Sudoku::from(s, &d).unwrap();
+        assert!(sudoku.has_unique_solution());
+        let solved = sudoku.solve().unwrap().to_line().into_bytes();
+        let diagonal: HashSet<u8> = (0..9).map(|i| solved[i * 9 + i]).collect();
+        assert_eq!(diagonal.len(), 9);
+    }
+}
@@ -0,0 +1,111 @@
+use std::fmt::Debug;
+use std::hash::Hash;
+
+/// The digits still possible for a cell, as [`Sudoku`](crate::Sudoku)
+/// keeps them, digits counting from 1. [`Candidates`] is the one the
+/// crate uses everywhere, and the default; another representation only
+/// has to implement this trait for the solver to run on it, by
+/// [`Sudoku::with_cells`](crate::Sudoku::with_cells).
+pub trait Cell: Copy + Default + Eq + Hash + Debug {
+    /// Every digit from 1 to `side`.
+    fn all(side: usize) -> Self;
+
+    fn contains(self, v: usize) -> bool;
+
+    fn insert(&mut self, v: usize);
+
+    fn remove(&mut self, v: usize);
+
+    fn len(self) -> usize;
+
+    #[inline]
+    fn is_empty(self) -> bool {
+        self.len() == 0
+    }
+
+    /// The only candidate left, if there is exactly one.
+    fn single(self) -> Option<usize>;
+
+    /// The candidates in ascending order.
+    fn iter(self) -> impl Iterator<Item = usize>;
+}
+
+/// The digits still possible for a cell, as a bitmask: bit `i - 1` is set
+/// when digit `i` is a candidate, so digits up to 16 fit.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, Debug)]
+pub struct Candidates(u16);
+
+impl Cell for Candidates {
+    #[inline]
+    fn all(side: usize) -> Candidates {
+        Candidates(((1u32 << side) - 1) as u16)
+    }
+
+    #[inline]
+    fn contains(self, v: usize) -> bool {
+        self.0 & (1 << (v - 1)) != 0
+    }
+
+    #[inline]
+    fn insert(&mut self, v: usize) {
+        self.0 |= 1 << (v - 1);
+    }
+
+    #[inline]
+    fn remove(&mut self, v: usize) {
+        self.0 &= !(1 << (v - 1));
+    }
+
+    #[inline]
+    fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    #[inline]
+    fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    #[inline]
+    fn single(self) -> Option<usize> {
+        (self.len() == 1).then(|| self.0.trailing_zeros() as usize + 1)
+    }
+
+    #[inline]
+    fn iter(self) -> impl Iterator<Item = usize> {
+        let mut bits = self.0;
+        std::iter::from_fn(move || {
+            (bits != 0).then(|| {
+                let v = bits.trailing_zeros() as usize + 1;
+                bits &= bits - 1;
+                v
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Candidates, Cell};
+
+    #[test]
+    fn bitmask_ops() {
+        let mut c = Candidates::all(9);
+        assert_eq!(c.len(), 9);
+        assert_eq!(c.iter().collect::<Vec<_>>(), (1..=9).collect::<Vec<_>>());
+        assert_eq!(c.single(), None);
+        (1..=9).filter(|v| *v != 4).for_each(|v| c.remove(v));
+        assert!(c.contains(4) && !c.contains(5));
+        assert_eq!(c.single(), Some(4));
+        c.insert(7);
+        assert_eq!(c.iter().collect::<Vec<_>>(), vec![4, 7]);
+        c.remove(4);
+        c.remove(7);
+        assert_eq!(c.len(), 0);
+        assert_eq!(c.single(), None);
+
+        let c = Candidates::all(16);
+        assert_eq!(c.len(), 16);
+        assert!(c.contains(16) && c.contains(1));
+    }
+}
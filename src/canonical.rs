@@ -0,0 +1,156 @@
+use crate::grid::Grid;
+
+/// The six orderings of three things.
+const PERMS: [[usize; 3]; 6] = [
+    [0, 1, 2],
+    [0, 2, 1],
+    [1, 0, 2],
+    [1, 2, 0],
+    [2, 0, 1],
+    [2, 1, 0],
+];
+
+/// Every way to reorder the nine rows, or columns, of a grid without
+/// breaking its boxes: the bands in any order, and the rows within each
+/// band in any order.
+fn line_orders() -> Vec<[usize; 9]> {
+    let mut orders = Vec::with_capacity(6 * 6 * 6 * 6);
+    for bands in PERMS {
+        for a in PERMS {
+            for b in PERMS {
+                for c in PERMS {
+                    let within = [a, b, c];
+                    let mut order = [0; 9];
+                    for (i, line) in order.iter_mut().enumerate() {
+                        *line = bands[i / 3] * 3 + within[i / 3][i % 3];
+                    }
+                    orders.push(order);
+                }
+            }
+        }
+    }
+    orders
+}
+
+impl Grid {
+    /// A representative of every grid equivalent to this one, so that two
+    /// grids are the same puzzle up to symmetry exactly when their forms
+    /// are equal.
+    ///
+    /// The symmetries are those preserving the rules: transposing,
+    /// reordering bands and stacks, reordering rows within a band and
+    /// columns within a stack, and relabeling the digits. The form is the
+    /// smallest of all the grids they lead to, compared cell by cell in
+    /// reading order, once each has its digits relabeled `1`, `2`, ... in
+    /// order of first appearance. Empty cells stay `0`.
+    ///
+    /// # Panics
+    ///
+    /// Unless the grid is 9x9.
+    pub fn canonicalize(&self) -> [u8; 81] {
+        let digits = self.digits();
+        assert_eq!(digits.len(), 81, "only 9x9 grids have a canonical form");
+        let orders = line_orders();
+        let mut best = [u8::MAX; 81];
+        let mut candidate = [0u8; 81];
+        for transpose in [false, true] {
+            for rows in &orders {
+                for cols in &orders {
+                    let cell = |k: usize| {
+                        let (r, c) = (rows[k / 9], cols[k % 9]);
+                        if transpose {
+                            digits[c * 9 + r]
+                        } else {
+                            digits[r * 9 + c]
+                        }
+                    };
+                    if relabel_below(cell, &best, &mut candidate) {
+                        best = candidate;
+                    }
+                }
+            }
+        }
+        best
+    }
+}
+
+/// Writes the cells `cell` gives, relabeled in order of first appearance,
+/// into `out`, returning whether they come before `best`. Gives up as soon
+/// as they can't.
+fn relabel_below<F: Fn(usize) -> u8>(cell: F, best: &[u8; 81], out: &mut [u8; 81]) -> bool {
+    let mut labels = [0u8; 10];
+    let mut next = 1;
+    let mut below = false;
+    for k in 0..81 {
+        let d = cell(k) as usize;
+        let v = if d == 0 {
+            0
+        } else {
+            if labels[d] == 0 {
+                labels[d] = next;
+                next += 1;
+            }
+            labels[d]
+        };
+        if !below {
+            if v > best[k] {
+                return false;
+            }
+            below = v < best[k];
+        }
+        out[k] = v;
+    }
+    below
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::SudokuBoard;
+    use crate::grid::Grid;
+
+    const PUZZLE: &str =
+        "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+
+    fn grid(s: &str) -> Grid {
+        Grid::parse(s, &SudokuBoard::new()).unwrap()
+    }
+
+    #[test]
+    fn equivalent_grids_share_a_form() {
+        let puzzle = grid(PUZZLE);
+        let digits = puzzle.digits();
+
+        let transposed: Vec<u8> = (0..81).map(|k| digits[(k % 9) * 9 + k / 9]).collect();
+        // Swap the first two bands and the last two columns, and relabel
+        // every digit d as 10 - d.
+        let shuffled: Vec<u8> = (0..81)
+            .map(|k| {
+                let (r, c) = (k / 9, k % 9);
+                let r = if r < 6 { (r + 3) % 6 } else { r };
+                let c = match c {
+                    7 => 8,
+                    8 => 7,
+                    c => c,
+                };
+                match digits[r * 9 + c] {
+                    0 => 0,
+                    d => 10 - d,
+                }
+            })
+            .collect();
+
+        let form = puzzle.canonicalize();
+        assert_eq!(Grid::from(&transposed[..]).canonicalize(), form);
+        assert_eq!(Grid::from(&shuffled[..]).canonicalize(), form);
+        assert_ne!(Grid::from(&transposed[..]), puzzle);
+
+        // Relabeled in order of first appearance, leading with empty cells.
+        assert_eq!(form.iter().filter(|d| **d != 0).count(), 17);
+        assert_eq!(form.iter().copied().find(|d| *d != 0), Some(1));
+
+        let other = grid(
+            "52...6.........7.13...........4..8..6......5...........418.........3..2...87.....",
+        );
+        assert_ne!(other.canonicalize(), form);
+    }
+}
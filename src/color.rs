@@ -0,0 +1,72 @@
+//! The `--color` mode: solutions with their givens in one color and the
+//! digits the solver found in another, by plain ANSI escapes.
+
+use sudoku_solver::{char_of, Sudoku};
+
+/// Bold blue, for the givens.
+const GIVEN: &str = "\x1b[1;34m";
+/// Green, for the digits the solver found.
+const FOUND: &str = "\x1b[32m";
+const RESET: &str = "\x1b[0m";
+
+/// `solved` laid out the way its `Display` lays out a solution, each digit
+/// colored by whether it was a given.
+pub fn paint(solved: &Sudoku) -> String {
+    let board = solved.board();
+    let (n, side) = (board.box_size(), board.side());
+    let sep = vec!["-".repeat(2 * n); n].join("+-");
+    let mut out = String::new();
+    for i in 0..side {
+        if i > 0 && i % n == 0 {
+            out.push_str(&sep);
+            out.push('\n');
+        }
+        for j in 0..side {
+            if j > 0 && j % n == 0 {
+                out.push_str("| ");
+            }
+            let k = i * side + j;
+            let digit = solved.value(k).map_or('.', char_of);
+            let color = if solved.is_given(k) { GIVEN } else { FOUND };
+            out.push_str(&format!("{}{}{} ", color, digit, RESET));
+        }
+        out.push('\n');
+    }
+    out.push('\n');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{paint, FOUND, GIVEN, RESET};
+    use sudoku_solver::{Sudoku, SudokuBoard};
+
+    #[test]
+    fn givens_stand_out() {
+        let board = SudokuBoard::new();
+        let s = "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+        let solved = Sudoku::from(s, &board).unwrap().solve().unwrap();
+        let painted = paint(&solved);
+        let top = painted.lines().next().unwrap();
+        assert!(top.starts_with(&format!("{}4{} {}1{} ", GIVEN, RESET, FOUND, RESET)));
+        // Without the escapes, the layout is that of the plain solution.
+        let plain = painted
+            .replace(GIVEN, "")
+            .replace(FOUND, "")
+            .replace(RESET, "");
+        assert_eq!(plain, solved.to_string());
+        assert_eq!(painted.matches(GIVEN).count(), solved.given_count());
+    }
+
+    #[test]
+    fn large_digits_keep_one_glyph() {
+        let board = SudokuBoard::with_box_size(4);
+        let solved = Sudoku::empty(&board).solve().unwrap();
+        let plain = paint(&solved)
+            .replace(GIVEN, "")
+            .replace(FOUND, "")
+            .replace(RESET, "");
+        assert_eq!(plain, solved.to_string());
+        assert!(plain.contains('G'));
+    }
+}
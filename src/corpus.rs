@@ -0,0 +1,151 @@
+//! The `stats` subcommand: figures about a whole collection of puzzles,
+//! rather than the solution of each.
+
+use sudoku_solver::{Difficulty, Grid, SudokuBoard};
+
+const BANDS: [Difficulty; 5] = [
+    Difficulty::Trivial,
+    Difficulty::Easy,
+    Difficulty::Medium,
+    Difficulty::Hard,
+    Difficulty::Diabolical,
+];
+
+/// What `stats` has found out so far about the puzzles it went through.
+#[derive(Default)]
+pub struct Corpus {
+    puzzles: usize,
+    /// Puzzles that don't parse, or whose givens clash in a unit.
+    malformed: usize,
+    solvable: usize,
+    unique: usize,
+    /// The clues of the well-formed puzzles: the fewest, their sum and the
+    /// most.
+    clues: Option<(usize, usize, usize)>,
+    /// The solvable puzzles in each difficulty band, in the order of
+    /// [`BANDS`].
+    bands: [usize; 5],
+}
+
+impl Corpus {
+    /// Adds the puzzle `s`.
+    pub fn add(&mut self, s: &str) {
+        self.puzzles += 1;
+        let board = SudokuBoard::standard();
+        let Some(grid) = Grid::parse(s, board)
+            .ok()
+            .filter(|g| g.validate(board).is_ok())
+        else {
+            self.malformed += 1;
+            return;
+        };
+        let clues = grid.digits().iter().filter(|d| **d != 0).count();
+        let (min, sum, max) = self.clues.unwrap_or((clues, 0, clues));
+        self.clues = Some((min.min(clues), sum + clues, max.max(clues)));
+        let Ok(sudoku) = grid.to_sudoku(board) else {
+            return;
+        };
+        match sudoku.count_solutions(2) {
+            0 => return,
+            1 => self.unique += 1,
+            _ => {}
+        }
+        self.solvable += 1;
+        let band = sudoku.difficulty();
+        self.bands[BANDS.iter().position(|b| *b == band).unwrap()] += 1;
+    }
+
+    /// The average clue count of the well-formed puzzles, if there are
+    /// any.
+    fn average_clues(&self) -> Option<f64> {
+        let well_formed = self.puzzles - self.malformed;
+        self.clues
+            .map(|(_, sum, _)| sum as f64 / well_formed as f64)
+    }
+
+    /// The figures as a table, one per line.
+    pub fn table(&self) -> String {
+        let mut rows = vec![
+            ("puzzles".to_string(), self.puzzles.to_string()),
+            ("malformed".to_string(), self.malformed.to_string()),
+            ("solvable".to_string(), self.solvable.to_string()),
+            ("unique".to_string(), self.unique.to_string()),
+        ];
+        if let (Some((min, _, max)), Some(average)) = (self.clues, self.average_clues()) {
+            rows.push((
+                "clues".to_string(),
+                format!("{} min, {:.1} average, {} max", min, average, max),
+            ));
+        }
+        for (band, count) in BANDS.iter().zip(self.bands) {
+            rows.push((format!("{:?}", band), count.to_string()));
+        }
+        let width = rows.iter().map(|(name, _)| name.len()).max().unwrap();
+        rows.iter()
+            .map(|(name, value)| format!("{:width$}  {}\n", name, value, width = width))
+            .collect()
+    }
+
+    /// The figures as one JSON object, `clues` being `null` when no
+    /// puzzle was well-formed.
+    pub fn json(&self) -> String {
+        let clues = match (self.clues, self.average_clues()) {
+            (Some((min, _, max)), Some(average)) => format!(
+                "{{\"min\":{},\"average\":{:.2},\"max\":{}}}",
+                min, average, max
+            ),
+            _ => "null".to_string(),
+        };
+        let bands: Vec<String> = BANDS
+            .iter()
+            .zip(self.bands)
+            .map(|(band, count)| format!("\"{:?}\":{}", band, count))
+            .collect();
+        format!(
+            "{{\"puzzles\":{},\"malformed\":{},\"solvable\":{},\"unique\":{},\"clues\":{},\"difficulty\":{{{}}}}}",
+            self.puzzles,
+            self.malformed,
+            self.solvable,
+            self.unique,
+            clues,
+            bands.join(",")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Corpus;
+
+    #[test]
+    fn figures_add_up() {
+        let mut corpus = Corpus::default();
+        assert_eq!(corpus.json().matches("null").count(), 1);
+        // Unique and trivial, unique and not, one with no solution, an
+        // empty grid and a malformed one.
+        corpus.add(
+            "..3.2.6..9..3.5..1..18.64....81.29..7.......8..67.82....26.95..8..2.3..9..5.1.3..",
+        );
+        corpus.add(
+            "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......",
+        );
+        corpus.add(
+            "49....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......",
+        );
+        corpus.add(&".".repeat(81));
+        corpus.add("12");
+        let table = corpus.table();
+        let rows: Vec<&str> = table.lines().collect();
+        assert_eq!(rows[0], "puzzles     5");
+        assert_eq!(rows[1], "malformed   1");
+        assert_eq!(rows[2], "solvable    3");
+        assert_eq!(rows[3], "unique      2");
+        assert_eq!(rows[4], "clues       0 min, 16.8 average, 32 max");
+        assert_eq!(rows.len(), 10);
+        let json = corpus.json();
+        assert!(json.starts_with(
+            "{\"puzzles\":5,\"malformed\":1,\"solvable\":3,\"unique\":2,\"clues\":{\"min\":0,"
+        ));
+        assert!(json.contains("\"difficulty\":{\"Trivial\":"));
+    }
+}
@@ -0,0 +1,199 @@
+use std::error::Error;
+use std::fmt;
+
+/// Why a puzzle string could not be turned into a [`Sudoku`](crate::Sudoku).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The given at `cell` contradicts the givens placed before it.
+    Contradiction { cell: usize },
+    /// The input described `got` cells instead of the `expected` number.
+    BadLength { expected: usize, got: usize },
+    /// `cell` holds `value`, which isn't a digit of the board.
+    BadDigit { cell: usize, value: usize },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Contradiction { cell } => {
+                write!(f, "given at cell {} contradicts the others", cell)
+            }
+            ParseError::BadLength { expected, got } => {
+                write!(f, "expected {} cells, got {}", expected, got)
+            }
+            ParseError::BadDigit { cell, value } => {
+                write!(f, "cell {} holds {}, which is not a digit", cell, value)
+            }
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Why a puzzle could not be solved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    /// The puzzle is well formed but has no completion.
+    NoSolution,
+    /// The puzzle could not be parsed in the first place.
+    InvalidInput(ParseError),
+    /// The search hit its guess budget before settling the puzzle either
+    /// way.
+    BudgetExceeded,
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::NoSolution => write!(f, "no solution"),
+            SolveError::InvalidInput(e) => write!(f, "invalid input: {}", e),
+            SolveError::BudgetExceeded => write!(f, "guess budget exceeded"),
+        }
+    }
+}
+
+impl Error for SolveError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SolveError::InvalidInput(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseError> for SolveError {
+    fn from(e: ParseError) -> Self {
+        SolveError::InvalidInput(e)
+    }
+}
+
+/// Two cells of the same unit holding the same digit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    /// The unit, numbered as on [`SudokuBoard`](crate::SudokuBoard).
+    pub unit: usize,
+    /// The repeated digit.
+    pub value: usize,
+    /// The two cells holding it, in the order the unit lists them.
+    pub cells: (usize, usize),
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} appears twice in unit {}, at cells {} and {}",
+            self.value, self.unit, self.cells.0, self.cells.1
+        )
+    }
+}
+
+impl Error for Conflict {}
+
+/// Why [`Grid::validate`](crate::Grid::validate) turned a grid down.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GridError {
+    /// The grid isn't laid out for the board: the wrong number of cells,
+    /// or a digit too large for it.
+    Malformed(ParseError),
+    /// Two cells of a unit hold the same digit.
+    Conflict(Conflict),
+}
+
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GridError::Malformed(e) => write!(f, "{}", e),
+            GridError::Conflict(c) => write!(f, "{}", c),
+        }
+    }
+}
+
+impl Error for GridError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GridError::Malformed(e) => Some(e),
+            GridError::Conflict(c) => Some(c),
+        }
+    }
+}
+
+impl From<ParseError> for GridError {
+    fn from(e: ParseError) -> Self {
+        GridError::Malformed(e)
+    }
+}
+
+impl From<Conflict> for GridError {
+    fn from(c: Conflict) -> Self {
+        GridError::Conflict(c)
+    }
+}
+
+/// Why a set of regions doesn't split a board into units, or a set of
+/// extra units can't be added to one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegionError {
+    /// A region lists a cell that isn't on the board.
+    OutOfRange { cell: usize },
+    /// A cell is listed more than once, so some other cell is left out.
+    Repeated { cell: usize },
+    /// An extra unit, numbered from 0 among the extra ones, doesn't hold 9
+    /// distinct cells.
+    BadUnit { unit: usize },
+}
+
+impl fmt::Display for RegionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegionError::OutOfRange { cell } => write!(f, "cell {} is off the board", cell),
+            RegionError::Repeated { cell } => write!(f, "cell {} is in more than one region", cell),
+            RegionError::BadUnit { unit } => {
+                write!(f, "extra unit {} doesn't hold 9 distinct cells", unit)
+            }
+        }
+    }
+}
+
+impl Error for RegionError {}
+
+/// Why [`Sudoku::play`](crate::Sudoku::play) turned a move down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveError {
+    /// The cell already holds a digit, given or deduced.
+    AlreadyFilled { cell: usize },
+    /// The digit has been ruled out for the cell.
+    NotACandidate { cell: usize, value: usize },
+    /// The digit is still a candidate, but placing it leaves some other
+    /// cell or unit with no room for a digit.
+    Contradiction { cell: usize, value: usize },
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveError::AlreadyFilled { cell } => write!(f, "cell {} is already filled", cell),
+            MoveError::NotACandidate { cell, value } => {
+                write!(f, "{} is not a candidate for cell {}", value, cell)
+            }
+            MoveError::Contradiction { cell, value } => {
+                write!(f, "{} at cell {} contradicts the grid", value, cell)
+            }
+        }
+    }
+}
+
+impl Error for MoveError {}
+
+/// Propagation ran into a cell, or a unit, with no room left for some
+/// digit: the grid has no solution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Contradiction;
+
+impl fmt::Display for Contradiction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the grid contradicts itself")
+    }
+}
+
+impl Error for Contradiction {}
@@ -0,0 +1,117 @@
+//! The format of Project Euler's problem 96, and of collections like it:
+//! each puzzle laid out one row per line, below a header line naming it,
+//! such as `Grid 01`.
+
+use crate::board::SudokuBoard;
+use crate::error::ParseError;
+use crate::sudoku::Sudoku;
+use std::io::{self, BufRead};
+
+/// Whether `line` is a row of a puzzle, all digits and `.`, rather than a
+/// header.
+fn is_row(line: &str) -> bool {
+    line.chars().all(|c| c.is_ascii_digit() || c == '.')
+}
+
+/// Reads puzzles laid out one row per line, `0` and `.` being empty cells,
+/// each below a header line. Any line that isn't blank or a row, that is
+/// any line with something other than digits and `.`, is a header, and
+/// starts a new puzzle. Blank lines are skipped, and rows before the first
+/// header make a puzzle whose header is empty.
+///
+/// The result is each header, trimmed, with the puzzle below it parsed
+/// like [`Sudoku::from_grid_lines`] parses it, so a puzzle with too few or
+/// too many rows is an `Err` among the results. Only failing to read stops
+/// the reader, the error saying on which line it happened.
+pub fn read_euler<'a>(
+    reader: impl BufRead,
+    board: &'a SudokuBoard,
+) -> io::Result<Vec<(String, Result<Sudoku<'a>, ParseError>)>> {
+    let mut sections: Vec<(String, Vec<String>)> = Vec::new();
+    for (n, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| io::Error::new(e.kind(), format!("line {}: {}", n + 1, e)))?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match sections.last_mut() {
+            _ if !is_row(line) => sections.push((line.to_string(), Vec::new())),
+            Some((_, rows)) => rows.push(line.to_string()),
+            None => sections.push((String::new(), vec![line.to_string()])),
+        }
+    }
+    Ok(sections
+        .into_iter()
+        .map(|(header, rows)| {
+            let rows: Vec<&str> = rows.iter().map(String::as_str).collect();
+            (header, Sudoku::from_grid_lines(&rows, board))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::read_euler;
+    use crate::board::SudokuBoard;
+    use crate::error::ParseError;
+    use std::io::Cursor;
+
+    /// The first two grids of Project Euler's problem 96.
+    const GRIDS: &str = "Grid 01
+003020600
+900305001
+001806400
+008102900
+700000008
+006708200
+002609500
+800203009
+005010300
+Grid 02
+200080300
+060070084
+030500209
+000105408
+000000000
+402706000
+301007040
+720040060
+004010003
+";
+
+    #[test]
+    fn puzzles_follow_their_headers() {
+        let board = SudokuBoard::new();
+        let puzzles = read_euler(Cursor::new(GRIDS), &board).unwrap();
+        let headers: Vec<&str> = puzzles.iter().map(|(h, _)| h.as_str()).collect();
+        assert_eq!(headers, ["Grid 01", "Grid 02"]);
+        let first = puzzles[0].1.as_ref().unwrap();
+        assert_eq!(first.given_count(), 32);
+        // The top row of the solution the problem statement shows.
+        assert_eq!(&first.solve().unwrap().to_line()[..9], "483921657");
+        assert!(puzzles[1].1.as_ref().unwrap().has_unique_solution());
+    }
+
+    #[test]
+    fn short_and_headless_puzzles() {
+        let board = SudokuBoard::new();
+        let rows: Vec<&str> = GRIDS.lines().skip(1).take(9).collect();
+        let input = format!("{}\n\nGrid 02\n{}\n", rows.join("\n"), rows[0]);
+        let puzzles = read_euler(Cursor::new(input), &board).unwrap();
+        assert_eq!(puzzles.len(), 2);
+        assert_eq!(puzzles[0].0, "");
+        assert!(puzzles[0].1.is_ok());
+        assert_eq!(puzzles[1].0, "Grid 02");
+        assert_eq!(
+            puzzles[1].1.as_ref().err(),
+            Some(&ParseError::BadLength {
+                expected: 81,
+                got: 9
+            })
+        );
+
+        let bad = Cursor::new(vec![b'G', b'\n', 0xff, b'\n']);
+        let e = read_euler(bad, &board).err().unwrap();
+        assert!(e.to_string().starts_with("line 2: "));
+    }
+}
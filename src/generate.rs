@@ -0,0 +1,169 @@
+//! The `generate` subcommand: new puzzles rather than solutions, one line
+//! each, for a puzzle book say.
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::io::{self, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+use sudoku_solver::{
+    generate_symmetric, generate_symmetric_with_difficulty, Difficulty, Sudoku, SudokuBoard,
+    SymmetryKind,
+};
+
+/// What `generate` was asked for.
+#[derive(Debug, PartialEq)]
+pub struct Request {
+    count: usize,
+    /// The band to aim for, see [`generate_symmetric_with_difficulty`].
+    /// Without one the puzzles are as sparse as they come.
+    difficulty: Option<Difficulty>,
+    symmetry: SymmetryKind,
+    /// The seed of the generator, the same seed always giving the same
+    /// puzzles. Without one it's drawn from the clock.
+    seed: Option<u64>,
+}
+
+impl Request {
+    /// Parses the arguments after `generate`, `None` if they don't make
+    /// sense.
+    pub fn parse(args: impl Iterator<Item = String>) -> Option<Request> {
+        let mut request = Request {
+            count: 1,
+            difficulty: None,
+            symmetry: SymmetryKind::None,
+            seed: None,
+        };
+        let mut args = args;
+        while let Some(arg) = args.next() {
+            let value = args.next()?;
+            match arg.as_str() {
+                "--count" => request.count = value.parse().ok().filter(|n| *n > 0)?,
+                "--difficulty" => request.difficulty = Some(difficulty(&value)?),
+                "--symmetry" => request.symmetry = symmetry(&value)?,
+                "--seed" => request.seed = Some(value.parse().ok()?),
+                _ => return None,
+            }
+        }
+        Some(request)
+    }
+
+    /// Writes the puzzles asked for to `out`, one line of givens each, the
+    /// way [`Sudoku::from`] reads them.
+    pub fn run(&self, mut out: impl Write) -> io::Result<()> {
+        let board = SudokuBoard::standard();
+        let seed = self.seed.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_nanos() as u64)
+        });
+        let mut rng = StdRng::seed_from_u64(seed);
+        for _ in 0..self.count {
+            let puzzle = match self.difficulty {
+                Some(target) => {
+                    generate_symmetric_with_difficulty(board, target, self.symmetry, &mut rng).0
+                }
+                None => generate_symmetric(board, 0, self.symmetry, &mut rng),
+            };
+            writeln!(out, "{}", givens_line(&puzzle))?;
+        }
+        Ok(())
+    }
+}
+
+/// The givens of `puzzle` as a line, `.` for every other cell, however
+/// many of those propagation has filled in.
+fn givens_line(puzzle: &Sudoku) -> String {
+    let mut line = vec!['.'; puzzle.board().cell_count()];
+    for (k, v) in puzzle.givens() {
+        line[k] = char::from_digit(v as u32, 10).unwrap_or('.');
+    }
+    line.into_iter().collect()
+}
+
+fn difficulty(name: &str) -> Option<Difficulty> {
+    Some(match name {
+        "trivial" => Difficulty::Trivial,
+        "easy" => Difficulty::Easy,
+        "medium" => Difficulty::Medium,
+        "hard" => Difficulty::Hard,
+        "diabolical" => Difficulty::Diabolical,
+        _ => return None,
+    })
+}
+
+fn symmetry(name: &str) -> Option<SymmetryKind> {
+    Some(match name {
+        "none" => SymmetryKind::None,
+        "rotational" => SymmetryKind::Rotational180,
+        "diagonal" => SymmetryKind::Diagonal,
+        "horizontal" => SymmetryKind::Horizontal,
+        "vertical" => SymmetryKind::Vertical,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Request;
+    use sudoku_solver::{Difficulty, Grid, Sudoku, SudokuBoard, SymmetryKind};
+
+    fn parse(args: &[&str]) -> Option<Request> {
+        Request::parse(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn options_parse() {
+        assert_eq!(
+            parse(&[]),
+            Some(Request {
+                count: 1,
+                difficulty: None,
+                symmetry: SymmetryKind::None,
+                seed: None
+            })
+        );
+        let request = parse(&[
+            "--count",
+            "10",
+            "--difficulty",
+            "medium",
+            "--symmetry",
+            "rotational",
+            "--seed",
+            "7",
+        ])
+        .unwrap();
+        assert_eq!(request.count, 10);
+        assert_eq!(request.difficulty, Some(Difficulty::Medium));
+        assert_eq!(request.symmetry, SymmetryKind::Rotational180);
+        assert_eq!(request.seed, Some(7));
+        assert_eq!(parse(&["--count", "0"]), None);
+        assert_eq!(parse(&["--count"]), None);
+        assert_eq!(parse(&["--difficulty", "impossible"]), None);
+        assert_eq!(parse(&["--symmetry", "radial"]), None);
+        assert_eq!(parse(&["--seed", "-1"]), None);
+        assert_eq!(parse(&["--grid", "x"]), None);
+    }
+
+    #[test]
+    fn seeds_repeat_puzzles() {
+        let run = |args: &[&str]| {
+            let mut out = Vec::new();
+            parse(args).unwrap().run(&mut out).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+        let args = ["--count", "2", "--symmetry", "rotational", "--seed", "3"];
+        let out = run(&args);
+        assert_eq!(out, run(&args));
+        assert_ne!(out, run(&["--count", "2", "--seed", "4"]));
+
+        let board = SudokuBoard::standard();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 2);
+        for line in lines {
+            assert!(Sudoku::from(line, board).unwrap().has_unique_solution());
+            let grid = Grid::parse(line, board).unwrap();
+            assert_eq!(grid.clue_symmetry(), SymmetryKind::Rotational180);
+        }
+    }
+}
@@ -0,0 +1,280 @@
+use crate::board::SudokuBoard;
+use crate::error::{Conflict, GridError, ParseError};
+use crate::parse::{digit_of, EmptyChars};
+use crate::sudoku::Sudoku;
+
+/// The digits of a puzzle exactly as written, `0` standing for an empty
+/// cell, with no board attached and no propagation done.
+///
+/// Where a [`Sudoku`](crate::Sudoku) only ever holds consistent candidates,
+/// a grid can hold anything, which makes it the place to look for mistakes
+/// in the givens. It is also the form to store a puzzle or a solution in:
+/// with the `serde` feature on, a grid serializes as a flat array of its
+/// digits.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Grid(Vec<u8>);
+
+impl Grid {
+    /// Reads the digits of a puzzle for `board`, following the same rules as
+    /// [`Sudoku::from`](crate::Sudoku::from).
+    pub fn parse(s: &str, board: &SudokuBoard) -> Result<Grid, ParseError> {
+        Grid::parse_with(s, board, &EmptyChars::default())
+    }
+
+    /// Like [`Grid::parse`], but with `empty` deciding which characters are
+    /// empty cells.
+    pub fn parse_with(
+        s: &str,
+        board: &SudokuBoard,
+        empty: &EmptyChars,
+    ) -> Result<Grid, ParseError> {
+        let digits: Vec<u8> = s
+            .chars()
+            .filter_map(|c| match digit_of(c, board.side()) {
+                Some(v) => Some(v as u8),
+                None => empty.contains(c).then_some(0),
+            })
+            .collect();
+        if digits.len() != board.cell_count() {
+            return Err(ParseError::BadLength {
+                expected: board.cell_count(),
+                got: digits.len(),
+            });
+        }
+        Ok(Grid(digits))
+    }
+
+    /// Places the digits of this grid on `board`, the way
+    /// [`Sudoku::from`] places the digits of a string.
+    pub fn to_sudoku<'a>(&self, board: &'a SudokuBoard) -> Result<Sudoku<'a>, ParseError> {
+        Sudoku::from_grid(self, board)
+    }
+
+    /// The digits in row-major order, `0` for empty cells.
+    #[inline]
+    pub fn digits(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Checks that the grid has one digit per cell of `board`, none too
+    /// large for it, the way [`Grid::to_sudoku`] does before placing any.
+    pub(crate) fn check(&self, board: &SudokuBoard) -> Result<(), ParseError> {
+        if self.0.len() != board.cell_count() {
+            return Err(ParseError::BadLength {
+                expected: board.cell_count(),
+                got: self.0.len(),
+            });
+        }
+        match self.0.iter().position(|v| *v as usize > board.side()) {
+            Some(cell) => Err(ParseError::BadDigit {
+                cell,
+                value: self.0[cell] as usize,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks that no unit of `board` holds the same digit twice, reporting
+    /// the first clash found, scanning units in order.
+    ///
+    /// A grid that doesn't fit the board in the first place, as one built
+    /// from bytes or deserialized may not, is reported as
+    /// [`GridError::Malformed`].
+    pub fn validate(&self, board: &SudokuBoard) -> Result<(), GridError> {
+        self.check(board)?;
+        for unit in 0..board.unit_count() {
+            let mut seen: Vec<Option<usize>> = vec![None; board.side() + 1];
+            for &cell in board.cells_in_unit(unit) {
+                let value = self.0[cell] as usize;
+                if value == 0 {
+                    continue;
+                }
+                match seen[value] {
+                    Some(first) => {
+                        return Err(GridError::Conflict(Conflict {
+                            unit,
+                            value,
+                            cells: (first, cell),
+                        }))
+                    }
+                    None => seen[value] = Some(cell),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The givens that can each be taken out, on their own, with the puzzle
+    /// on `board` still having a unique solution. Empty if the puzzle
+    /// doesn't have a unique solution to begin with.
+    ///
+    /// This is single-clue redundancy only: taking out two of the cells
+    /// returned may well leave the puzzle ambiguous, as with a solved grid,
+    /// where every cell is redundant on its own.
+    pub fn redundant_givens(&self, board: &SudokuBoard) -> Vec<usize> {
+        let unique = |grid: &Grid| {
+            grid.to_sudoku(board)
+                .is_ok_and(|sudoku| sudoku.has_unique_solution())
+        };
+        if !unique(self) {
+            return Vec::new();
+        }
+        let mut grid = self.clone();
+        let mut redundant = Vec::new();
+        for k in 0..self.0.len() {
+            let v = self.0[k];
+            if v == 0 {
+                continue;
+            }
+            grid.0[k] = 0;
+            if unique(&grid) {
+                redundant.push(k);
+            }
+            grid.0[k] = v;
+        }
+        redundant
+    }
+}
+
+impl From<&[u8]> for Grid {
+    /// The digits in row-major order, `0` for empty cells, taken as they
+    /// are: checking them is left to [`Grid::validate`], [`Grid::hint`] and
+    /// [`Grid::to_sudoku`], which report a grid that doesn't fit the board.
+    fn from(digits: &[u8]) -> Self {
+        Grid(digits.to_vec())
+    }
+}
+
+impl From<&Sudoku<'_>> for Grid {
+    /// The determined cells of `sudoku`, leaving the others empty.
+    fn from(sudoku: &Sudoku<'_>) -> Self {
+        Grid(
+            (0..sudoku.board().cell_count())
+                .map(|k| sudoku.value(k).unwrap_or(0) as u8)
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+    use crate::board::SudokuBoard;
+    use crate::error::{Conflict, GridError, ParseError};
+    use crate::sudoku::Sudoku;
+
+    const PUZZLE: &str =
+        "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+
+    #[test]
+    fn sudoku_round_trip() {
+        let board = SudokuBoard::new();
+        let solved = Sudoku::from(PUZZLE, &board).unwrap().solve().unwrap();
+        let grid = Grid::from(&solved);
+        assert!(grid.digits().iter().all(|d| (1..=9).contains(d)));
+        assert_eq!(grid.to_sudoku(&board).unwrap().to_line(), solved.to_line());
+
+        let mut digits = grid.digits().to_vec();
+        digits[3] = 10;
+        assert_eq!(
+            Grid(digits).to_sudoku(&board).err(),
+            Some(ParseError::BadDigit { cell: 3, value: 10 })
+        );
+        assert_eq!(
+            Grid(vec![0; 16]).to_sudoku(&board).err(),
+            Some(ParseError::BadLength {
+                expected: 81,
+                got: 16
+            })
+        );
+    }
+
+    #[test]
+    fn redundant_givens_one_at_a_time() {
+        let board = SudokuBoard::new();
+        let grid = Grid::parse(PUZZLE, &board).unwrap();
+        assert_eq!(grid.redundant_givens(&board), Vec::<usize>::new());
+
+        // With its second cell given too, the puzzle can spare that cell.
+        let solved = Grid::from(&grid.to_sudoku(&board).unwrap().solve().unwrap());
+        let mut extra = grid.clone();
+        extra.0[1] = solved.0[1];
+        assert!(extra.redundant_givens(&board).contains(&1));
+
+        // Each cell of a solution is redundant on its own, not all together.
+        let all: Vec<usize> = (0..81).collect();
+        assert_eq!(solved.redundant_givens(&board), all);
+
+        let ambiguous = Grid(vec![0; 81]);
+        assert_eq!(ambiguous.redundant_givens(&board), Vec::<usize>::new());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let board = SudokuBoard::new();
+        let solved = Sudoku::from(PUZZLE, &board).unwrap().solve().unwrap();
+        let grid = Grid::from(&solved);
+        let json = serde_json::to_string(&grid).unwrap();
+        assert!(json.starts_with("[4,1,7,3,6,9,8,2,5,"));
+        let back: Grid = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, grid);
+        assert_eq!(back.to_sudoku(&board).unwrap().to_line(), solved.to_line());
+    }
+
+    #[test]
+    fn validate_finds_duplicates() {
+        let board = SudokuBoard::new();
+        assert_eq!(
+            Grid::parse(PUZZLE, &board).unwrap().validate(&board),
+            Ok(())
+        );
+
+        // A second 4 in the first column, six rows down.
+        let mut bad: Vec<char> = PUZZLE.chars().collect();
+        bad[54] = '4';
+        let bad: String = bad.into_iter().collect();
+        assert_eq!(
+            Grid::parse(&bad, &board).unwrap().validate(&board),
+            Err(GridError::Conflict(Conflict {
+                unit: 9,
+                value: 4,
+                cells: (0, 54)
+            }))
+        );
+
+        // Same box, different row and column.
+        let bad = format!("5.........5{}", ".".repeat(70));
+        assert_eq!(
+            Grid::parse(&bad, &board).unwrap().validate(&board),
+            Err(GridError::Conflict(Conflict {
+                unit: 18,
+                value: 5,
+                cells: (0, 10)
+            }))
+        );
+    }
+
+    #[test]
+    fn validate_rejects_malformed_grids() {
+        let board = SudokuBoard::new();
+        assert_eq!(
+            Grid::from(&[0; 16][..]).validate(&board),
+            Err(GridError::Malformed(ParseError::BadLength {
+                expected: 81,
+                got: 16
+            }))
+        );
+        let mut digits = [0; 81];
+        digits[7] = 12;
+        assert_eq!(
+            Grid::from(&digits[..]).validate(&board),
+            Err(GridError::Malformed(ParseError::BadDigit {
+                cell: 7,
+                value: 12
+            }))
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use crate::board::SudokuBoard;
+use crate::candidates::{Candidates, Cell};
+use crate::error::ParseError;
+use crate::grid::Grid;
+
+/// A cell that can be filled in by logic alone, and why.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hint {
+    pub cell: usize,
+    pub value: usize,
+    pub kind: HintKind,
+}
+
+/// The reasoning behind a [`Hint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HintKind {
+    /// Every other digit is already taken by one of the cell's peers.
+    NakedSingle,
+    /// No other cell of `unit` can hold the digit.
+    HiddenSingle { unit: usize },
+}
+
+impl Grid {
+    /// The next empty cell a solver working by hand could fill in, if any.
+    ///
+    /// Only the digits placed so far count: each empty cell's candidates are
+    /// worked out from its peers, once, and the first naked single is
+    /// returned, or failing that, the first hidden single. `None` means
+    /// the grid can't be advanced without deeper reasoning or a guess.
+    ///
+    /// Fails, as [`Grid::to_sudoku`] does, if the grid doesn't have one
+    /// digit per cell of `board`, or holds a digit too large for it.
+    pub fn hint(&self, board: &SudokuBoard) -> Result<Option<Hint>, ParseError> {
+        self.check(board)?;
+        let digits = self.digits();
+        let candidates: Vec<Candidates> = (0..digits.len())
+            .map(|k| {
+                let mut c = Candidates::default();
+                if digits[k] == 0 {
+                    c = Candidates::all(board.side());
+                    board
+                        .neighbors(k)
+                        .iter()
+                        .filter(|n| digits[**n] != 0)
+                        .for_each(|n| c.remove(digits[*n] as usize));
+                }
+                c
+            })
+            .collect();
+
+        let naked = candidates.iter().enumerate().find_map(|(cell, c)| {
+            c.single().map(|value| Hint {
+                cell,
+                value,
+                kind: HintKind::NakedSingle,
+            })
+        });
+        Ok(naked.or_else(|| {
+            (0..board.unit_count()).find_map(|unit| {
+                (1..=board.side()).find_map(|value| {
+                    let cells = board.cells_in_unit(unit);
+                    let mut places = cells.iter().filter(|k| candidates[**k].contains(value));
+                    match (places.next(), places.next()) {
+                        (Some(&cell), None) => Some(Hint {
+                            cell,
+                            value,
+                            kind: HintKind::HiddenSingle { unit },
+                        }),
+                        _ => None,
+                    }
+                })
+            })
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Hint, HintKind};
+    use crate::board::SudokuBoard;
+    use crate::error::ParseError;
+    use crate::grid::Grid;
+    use crate::sudoku::Sudoku;
+
+    #[test]
+    fn naked_single() {
+        let board = SudokuBoard::new();
+        let s = format!("12345678.{}", ".".repeat(72));
+        let grid = Grid::parse(&s, &board).unwrap();
+        assert_eq!(
+            grid.hint(&board),
+            Ok(Some(Hint {
+                cell: 8,
+                value: 9,
+                kind: HintKind::NakedSingle
+            }))
+        );
+    }
+
+    #[test]
+    fn hidden_single() {
+        let board = SudokuBoard::new();
+        // 1s in the second and third rows and columns leave the first row,
+        // scanned first, a single place for its 1.
+        let mut s: Vec<char> = ".".repeat(81).chars().collect();
+        for cell in [12, 24, 28, 56] {
+            s[cell] = '1';
+        }
+        let s: String = s.into_iter().collect();
+        let grid = Grid::parse(&s, &board).unwrap();
+        assert_eq!(
+            grid.hint(&board),
+            Ok(Some(Hint {
+                cell: 0,
+                value: 1,
+                kind: HintKind::HiddenSingle { unit: 0 }
+            }))
+        );
+    }
+
+    #[test]
+    fn no_hint_without_deductions() {
+        let board = SudokuBoard::new();
+        let grid = Grid::parse(&".".repeat(81), &board).unwrap();
+        assert_eq!(grid.hint(&board), Ok(None));
+    }
+
+    #[test]
+    fn sudoku_hints_at_open_cells() {
+        let board = SudokuBoard::new();
+        // Propagation fills in the 9 itself, leaving nothing to hint at.
+        let s = format!("12345678.{}", ".".repeat(72));
+        let sudoku = Sudoku::from(&s, &board).unwrap();
+        assert_eq!(sudoku.value(8), Some(9));
+        assert_eq!(sudoku.hint(), None);
+        assert_eq!(Sudoku::empty(&board).hint(), None);
+
+        // The first row's last place for a 1 goes when the cell holding
+        // it is left with a single candidate: propagation clears that
+        // candidate out of its peers, but leaves the hidden single in the
+        // first cell for the hint.
+        let mut sudoku = Sudoku::empty(&board);
+        for k in 1..8 {
+            assert!(sudoku.eliminate(k, 1));
+        }
+        assert!(sudoku.restrict(8, &[1, 2]));
+        assert!(sudoku.eliminate(8, 1));
+        assert_eq!(sudoku.value(0), None);
+        assert_eq!(
+            sudoku.hint(),
+            Some(Hint {
+                cell: 0,
+                value: 1,
+                kind: HintKind::HiddenSingle { unit: 0 }
+            })
+        );
+        assert!(sudoku.play(0, 1).is_ok());
+        assert_ne!(sudoku.hint().map(|h| h.cell), Some(0));
+    }
+
+    #[test]
+    fn malformed_grid_has_no_hint() {
+        let board = SudokuBoard::new();
+        assert_eq!(
+            Grid::from(&[0; 80][..]).hint(&board),
+            Err(ParseError::BadLength {
+                expected: 81,
+                got: 80
+            })
+        );
+        let mut digits = [0; 81];
+        digits[40] = 10;
+        assert_eq!(
+            Grid::from(&digits[..]).hint(&board),
+            Err(ParseError::BadDigit {
+                cell: 40,
+                value: 10
+            })
+        );
+    }
+}
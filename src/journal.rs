@@ -0,0 +1,103 @@
+/// Observes the solver at work. Every hook does nothing by default, so a
+/// plain solve pays nothing for it.
+pub(crate) trait Journal {
+    /// Propagation removed `value` from the candidates of `cell`.
+    #[inline]
+    fn removed(&mut self, _cell: usize, _value: usize) {}
+
+    /// Propagation narrowed `cell` down to `value`.
+    #[inline]
+    fn assigned(&mut self, _cell: usize, _value: usize) {}
+
+    /// The search tried `value` for `cell`.
+    #[inline]
+    fn guessed(&mut self, _cell: usize, _value: usize) {}
+
+    /// Guessing `value` for `cell` led nowhere and was rolled back.
+    #[inline]
+    fn backtracked(&mut self, _cell: usize, _value: usize) {}
+
+    /// Whether the search should give up rather than make another guess.
+    #[inline]
+    fn exhausted(&self) -> bool {
+        false
+    }
+}
+
+impl Journal for () {}
+
+/// Caps the guesses of a search: once `left` runs out, the next guess is
+/// refused and the search unwinds.
+pub(crate) struct Budget {
+    pub(crate) left: usize,
+}
+
+impl Journal for Budget {
+    #[inline]
+    fn guessed(&mut self, _: usize, _: usize) {
+        self.left -= 1;
+    }
+
+    #[inline]
+    fn exhausted(&self) -> bool {
+        self.left == 0
+    }
+}
+
+/// The undo log of an in-place search: remembers every removed candidate
+/// so a failed branch can be rolled back, and forwards everything to an
+/// inner journal.
+pub(crate) struct Trail<J> {
+    removed: Vec<(usize, usize)>,
+    pub(crate) journal: J,
+}
+
+impl<J: Journal> Trail<J> {
+    pub(crate) fn new(journal: J) -> Trail<J> {
+        Trail {
+            removed: Vec::new(),
+            journal,
+        }
+    }
+
+    /// A point to roll back to later.
+    #[inline]
+    pub(crate) fn mark(&self) -> usize {
+        self.removed.len()
+    }
+
+    /// The candidates removed since `mark`, most recent first, forgetting
+    /// them.
+    #[inline]
+    pub(crate) fn rewind(&mut self, mark: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.removed.drain(mark..).rev()
+    }
+}
+
+impl<J: Journal> Journal for Trail<J> {
+    #[inline]
+    fn removed(&mut self, cell: usize, value: usize) {
+        self.removed.push((cell, value));
+        self.journal.removed(cell, value);
+    }
+
+    #[inline]
+    fn assigned(&mut self, cell: usize, value: usize) {
+        self.journal.assigned(cell, value);
+    }
+
+    #[inline]
+    fn guessed(&mut self, cell: usize, value: usize) {
+        self.journal.guessed(cell, value);
+    }
+
+    #[inline]
+    fn backtracked(&mut self, cell: usize, value: usize) {
+        self.journal.backtracked(cell, value);
+    }
+
+    #[inline]
+    fn exhausted(&self) -> bool {
+        self.journal.exhausted()
+    }
+}
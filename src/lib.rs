@@ -0,0 +1,51 @@
+//! A Sudoku solver after [Peter Norvig's](https://norvig.com/sudoku.html),
+//! combining constraint propagation with a depth-first search.
+//!
+//! ```
+//! use sudoku_solver::{Sudoku, SudokuBoard};
+//!
+//! let board = SudokuBoard::new();
+//! let puzzle = "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+//! let solved = Sudoku::from(puzzle, &board).unwrap().solve().unwrap();
+//! assert!(solved.is_solved());
+//! ```
+
+mod batch;
+mod board;
+mod candidates;
+mod canonical;
+mod error;
+mod euler;
+mod grid;
+mod hint;
+mod journal;
+mod parse;
+mod sadman;
+mod stats;
+mod sudoku;
+mod symmetry;
+mod trace;
+#[cfg(feature = "wasm")]
+mod wasm;
+
+pub use batch::{solve_all, solve_stream, solve_stream_with_progress};
+pub use board::SudokuBoard;
+pub use candidates::{Candidates, Cell};
+pub use error::{
+    Conflict, Contradiction, GridError, MoveError, ParseError, RegionError, SolveError,
+};
+pub use euler::read_euler;
+pub use grid::Grid;
+pub use hint::{Hint, HintKind};
+pub use parse::{char_of, EmptyChars};
+pub use sadman::read_sdm;
+pub use stats::{Difficulty, SolveStats};
+pub use sudoku::{
+    generate, generate_symmetric, generate_symmetric_with_difficulty, generate_with_difficulty,
+    Cage, Constraint, LogicalResult, Mark, SamuraiSudoku, Sudoku, SudokuBuilder, Technique,
+    Techniques, Thermo, ValueOrder,
+};
+pub use symmetry::SymmetryKind;
+pub use trace::Step;
+#[cfg(feature = "wasm")]
+pub use wasm::solve_line;
@@ -1,217 +1,656 @@
-use bitvector::BitVector;
-use core::fmt;
-use std::collections::HashSet;
-use std::io;
-
-struct SudokuBoard {
-    units: Vec<Vec<usize>>,
-    units_for: Vec<Vec<usize>>,
-    neighbors: Vec<HashSet<usize>>,
-}
-
-impl SudokuBoard {
-    fn new() -> SudokuBoard {
-        let mut units: Vec<Vec<usize>> = vec![Vec::new(); 27];
-        let mut units_for: Vec<Vec<usize>> = vec![Vec::new(); 81];
-        let mut neighbors: Vec<HashSet<usize>> = vec![HashSet::new(); 81];
-
-        for i in 0..9 {
-            for j in 0..9 {
-                let p = i * 9 + j;
-                let x = [i, 9 + j, 18 + (i / 3) * 3 + j / 3];
-                for k in 0..3 {
-                    units[x[k]].push(p);
-                    units_for[p].push(x[k]);
-                }
-            }
+use corpus::Corpus;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+use sudoku_solver::{
+    read_euler, solve_stream_with_progress, Grid, SolveError, Sudoku, SudokuBoard,
+};
+
+mod color;
+mod corpus;
+mod generate;
+mod repl;
+
+const USAGE: &str =
+    "usage: sudoku-solver [--grid] [--json | --all[=N] | --check | --time] [--dump-on-fail] [--progress] [--color] [FILE]
+       sudoku-solver --format euler [FILE]
+       sudoku-solver stats [--grid] [--json] [--progress] [FILE]
+       sudoku-solver generate [--count N] [--difficulty BAND] [--symmetry KIND] [--seed N]
+       sudoku-solver --interactive";
+
+/// How many solutions `--all` prints when not told a number.
+const ALL_CAP: usize = 100;
+
+/// How many puzzles `--progress` lets go by between counts.
+const PROGRESS_EVERY: usize = 1000;
+
+/// What to do, from the command line.
+#[derive(Default)]
+struct Options {
+    /// Puzzles span several lines each, see [`Puzzles`].
+    grid: bool,
+    /// One JSON object per puzzle on `stdout`, errors included.
+    json: bool,
+    /// Print up to this many solutions per puzzle, rather than just one.
+    all: Option<usize>,
+    /// A summary line per puzzle, see [`check`], rather than a solution.
+    check: bool,
+    /// How long each solve took after its solution, and totals at the end,
+    /// see [`Timings`].
+    time: bool,
+    /// After a puzzle with no solution, the grid propagation left it in,
+    /// see [`Sudoku::propagated`].
+    dump_on_fail: bool,
+    /// A running count of the puzzles done on `stderr`, see [`Progress`].
+    progress: bool,
+    /// Solutions with their givens picked out, see [`color::paint`].
+    color: bool,
+    /// One puzzle from `stdin`, worked through by commands, see [`repl`].
+    interactive: bool,
+    /// Puzzles come below header lines naming them, see [`solve_euler`].
+    euler: bool,
+    /// Figures about the whole collection rather than solutions, see
+    /// [`corpus::Corpus`].
+    stats: bool,
+    path: Option<String>,
+}
+
+impl Options {
+    fn parse(args: impl Iterator<Item = String>) -> Option<Options> {
+        let mut options = Options::default();
+        let mut args = args.peekable();
+        if args.next_if(|a| a == "stats").is_some() {
+            options.stats = true;
         }
-        for k in 0..neighbors.len() {
-            for i in 0..units_for[k].len() {
-                for j in 0..9 {
-                    let u: usize = units_for[k][i];
-                    let v = units[u][j];
-                    if v != k {
-                        neighbors[k].insert(v);
-                    }
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--grid" => options.grid = true,
+                "--json" => options.json = true,
+                "--all" => options.all = Some(ALL_CAP),
+                "--check" => options.check = true,
+                "--time" => options.time = true,
+                "--dump-on-fail" => options.dump_on_fail = true,
+                "--progress" => options.progress = true,
+                "--color" => options.color = true,
+                "--interactive" => options.interactive = true,
+                "--format" => {
+                    args.next().filter(|f| f == "euler")?;
+                    options.euler = true;
+                }
+                _ if arg.starts_with("--all=") => {
+                    options.all = Some(arg["--all=".len()..].parse().ok().filter(|n| *n > 0)?)
                 }
+                _ if arg.starts_with("--") || options.path.is_some() => return None,
+                _ => options.path = Some(arg),
             }
         }
+        let modes = [
+            options.json,
+            options.all.is_some(),
+            options.check,
+            options.time,
+        ];
+        let plain = !options.grid
+            && !options.dump_on_fail
+            && !options.progress
+            && !options.color
+            && !options.stats
+            && modes.iter().all(|m| !m);
+        let alone = plain && !options.euler && options.path.is_none();
+        // Only solving by the default mode, timed or not, can fail.
+        let dumps = !options.dump_on_fail || modes[..3].iter().all(|m| !m);
+        // Only the default mode and `--all` print solutions as grids.
+        let colors = !options.color || !(options.json || options.check);
+        (modes.iter().filter(|m| **m).count() <= 1 && dumps && colors)
+            .then_some(options)
+            .filter(|o| !o.interactive || alone)
+            .filter(|o| !o.euler || plain)
+            .filter(|o| !o.stats || !(o.dump_on_fail || o.color || modes[1..].iter().any(|m| *m)))
+    }
+}
 
-        SudokuBoard {
-            units,
-            units_for,
-            neighbors,
+/// Gathers lines into puzzles for the modes [`solve_stream`] doesn't cover.
+/// Normally each line is a puzzle, while in grid mode puzzles span as many
+/// lines as it takes to fill a board, a blank line also ending one. Either
+/// way blank lines are never puzzles themselves, so blank lines between
+/// puzzles and at the end of the input are fine.
+struct Puzzles {
+    grid: bool,
+    /// The puzzle being gathered, with the number of its first line.
+    current: Option<(usize, String)>,
+}
+
+impl Puzzles {
+    /// Adds line number `n`, returning the puzzle it completes, if any.
+    fn push(&mut self, n: usize, line: String) -> Option<(usize, String)> {
+        if line.trim().is_empty() {
+            return self.current.take();
+        }
+        if !self.grid {
+            return Some((n, line));
         }
+        let (_, puzzle) = self.current.get_or_insert_with(|| (n, String::new()));
+        puzzle.push_str(&line);
+        let cells = puzzle
+            .chars()
+            .filter(|c| c.is_ascii_digit() || *c == '.')
+            .count();
+        if cells >= SudokuBoard::standard().cell_count() {
+            return self.current.take();
+        }
+        None
+    }
+
+    /// The puzzle left over at the end of the input, if any.
+    fn finish(&mut self) -> Option<(usize, String)> {
+        self.current.take()
     }
 }
 
-#[derive(Clone)]
-struct Sudoku<'a> {
-    board: &'a SudokuBoard,
-    cells: Vec<BitVector>,
+/// The count of puzzles done, kept up to date on one line of `stderr` for
+/// `--progress`, so that a long run shows it's still going.
+struct Progress {
+    on: bool,
+    done: usize,
+    /// The count last shown.
+    shown: usize,
 }
 
-impl<'a> Sudoku<'a> {
-    fn new(board: &'a SudokuBoard) -> Sudoku<'a> {
-        let mut cells: Vec<BitVector> = vec![BitVector::new(10); 81];
-        cells.iter_mut().for_each(|c| {
-            for i in 1..=9 {
-                c.insert(i);
-            }
-        });
-        Sudoku { board, cells }
+impl Progress {
+    /// Counts one more puzzle done, showing the count every
+    /// [`PROGRESS_EVERY`] puzzles.
+    fn tick(&mut self) {
+        self.done += 1;
+        if self.done.is_multiple_of(PROGRESS_EVERY) {
+            self.show();
+        }
     }
 
-    fn from(s: &str, board: &'a SudokuBoard) -> Sudoku<'a> {
-        let mut sudoku = Sudoku::new(board);
-        for (k, c) in s
-            .chars()
-            .filter(|c| (*c >= '1' && *c <= '9') || *c == '0' || *c == '.')
-            .enumerate()
-        {
-            if c >= '1' && c <= '9' {
-                let v: usize = c.to_digit(10).unwrap() as usize;
-                if !sudoku.assign(k, v) {
-                    panic!("Invalid Sudoku")
-                }
-            }
+    fn show(&mut self) {
+        if self.on && self.shown != self.done {
+            eprint!("\r{} puzzles done", self.done);
+            self.shown = self.done;
         }
-        sudoku
     }
 
-    fn assign(&mut self, k: usize, v: usize) -> bool {
-        (1..=9)
-            .into_iter()
-            .filter(|i| *i != v)
-            .all(|i| self.eliminate(k, i))
+    /// Shows the final count, and ends its line.
+    fn finish(&mut self) {
+        self.show();
+        if self.shown > 0 {
+            eprintln!();
+        }
     }
+}
 
-    fn eliminate(&mut self, k: usize, v: usize) -> bool {
-        if !self.cells[k].contains(v) {
-            true
-        } else {
-            self.cells[k].remove(v);
-            match self.cells[k].len() {
-                0 => false,
-                1 => {
-                    let val = self.uniq_val(k);
-                    self.board.neighbors[k]
-                        .iter()
-                        .all(|n| self.eliminate(*n, val))
-                }
-                _ => self.board.units_for[k]
-                    .iter()
-                    .map(|i| &self.board.units[*i])
-                    .all(|u| {
-                        let ps: Vec<&usize> =
-                            u.iter().filter(|p| self.cells[**p].contains(v)).collect();
-                        match ps.len() {
-                            0 => false,
-                            1 => self.assign(*ps[0], v),
-                            _ => true,
-                        }
-                    }),
-            }
+/// How long the solves of a run took, for `--time`.
+#[derive(Default)]
+struct Timings {
+    count: u32,
+    total: Duration,
+    /// The longest solve, with the number of the line its puzzle started on.
+    max: Option<(Duration, usize)>,
+}
+
+impl Timings {
+    /// Adds the solve of the puzzle on line `n`.
+    fn record(&mut self, n: usize, took: Duration) {
+        self.count += 1;
+        self.total += took;
+        if self.max.is_none_or(|(max, _)| took > max) {
+            self.max = Some((took, n));
         }
     }
 
-    #[inline]
-    fn uniq_val(&self, k: usize) -> usize {
-        assert_eq!(self.cells[k].len(), 1);
-        self.cells[k].iter().nth(0).unwrap()
+    /// The line `--time` ends its output with, or `None` if nothing was
+    /// solved.
+    fn summary(&self) -> Option<String> {
+        let (max, n) = self.max?;
+        Some(format!(
+            "{} puzzles in {:.2?}: {:.2?} on average, {:.2?} at most, on line {}",
+            self.count,
+            self.total,
+            self.total / self.count,
+            max,
+            n + 1
+        ))
     }
+}
 
-    #[inline]
-    fn is_solved(&self) -> bool {
-        self.cells.iter().all(|k| k.len() == 1)
+/// `s` as a JSON string literal.
+fn json_string(s: &str) -> String {
+    let mut out = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
     }
+    out.push('"');
+    out
+}
 
-    #[inline]
-    fn smaller_cell(&self) -> usize {
-        self.cells
-            .iter()
-            .enumerate()
-            .filter(|(_, k)| k.len() > 1)
-            .min_by_key(|(_, k)| k.len())
-            .map(|(i, _)| i)
-            .unwrap()
+/// `solution` the way `Display` shows it, or painted by [`color::paint`].
+fn show(solution: &Sudoku, color: bool) -> String {
+    if color {
+        color::paint(solution)
+    } else {
+        solution.to_string()
     }
+}
 
-    fn solve(&self) -> Option<Sudoku<'a>> {
-        if self.is_solved() {
-            Some(self.clone())
-        } else {
-            let k = self.smaller_cell();
-            self.cells[k].iter().fold(None, |acc, v| match acc {
-                Some(_) => acc,
-                None => {
-                    let mut s = self.clone();
-                    s.assign(k, v).then(|| s.solve()).flatten()
-                }
-            })
+/// Prints up to `cap` solutions of `sudoku`, then a line saying whether its
+/// solution is unique, how many there are or that there are more than
+/// `cap`.
+fn solve_all(n: usize, sudoku: &Sudoku, cap: usize, color: bool) {
+    let mut count = 0;
+    for solution in sudoku.solutions().take(cap.saturating_add(1)) {
+        count += 1;
+        if count <= cap {
+            println!("{}", show(&solution, color));
         }
     }
+    match count {
+        0 => println!("line {}: {}", n + 1, SolveError::NoSolution),
+        1 => println!("unique"),
+        _ if count > cap => println!("more than {} solutions", cap),
+        _ => println!("{} solutions", count),
+    }
+}
+
+/// Prints a line saying whether `s` is well-formed, how many givens it has
+/// and whether its solution is unique, without solving it any further than
+/// that takes.
+fn check(n: usize, s: &str) {
+    let board = SudokuBoard::standard();
+    let grid = match Grid::parse(s, board) {
+        Ok(grid) => grid,
+        Err(e) => return println!("line {}: {}", n + 1, SolveError::from(e)),
+    };
+    if let Err(e) = grid.validate(board) {
+        return println!("line {}: invalid input: {}", n + 1, e);
+    }
+    let solutions = match grid.to_sudoku(board) {
+        Ok(sudoku) => sudoku.count_solutions(2),
+        Err(_) => 0,
+    };
+    let clues = grid.digits().iter().filter(|d| **d != 0).count();
+    let solutions = ["no solution", "unique", "multiple solutions"][solutions];
+    println!("line {}: {} clues, {}", n + 1, clues, solutions);
 }
 
-impl<'a> fmt::Display for Sudoku<'a> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fn bitvector_to_str(b: &BitVector) -> String {
-            let n = b.iter().fold(0, |acc, n| acc * 10 + n);
-            n.to_string()
+fn solve(options: &Options, timings: &mut Timings, n: usize, s: &str) {
+    if options.check {
+        return check(n, s);
+    }
+    if let Some(cap) = options.all {
+        match Sudoku::from(s, SudokuBoard::standard()) {
+            Ok(sudoku) => solve_all(n, &sudoku, cap, options.color),
+            Err(e) => println!("line {}: {}", n + 1, SolveError::from(e)),
         }
+        return;
+    }
+    let mut took = None;
+    let mut stuck = None;
+    let solved = Sudoku::from(s, SudokuBoard::standard())
+        .map_err(SolveError::from)
+        .and_then(|sudoku| {
+            let start = Instant::now();
+            let solved = sudoku.try_solve();
+            took = Some(start.elapsed());
+            if solved.is_err() && options.dump_on_fail {
+                stuck = Some(sudoku.propagated());
+            }
+            solved
+        });
+    match (options.json, solved) {
+        (false, Ok(solved)) => println!("{}", show(&solved, options.color)),
+        (false, Err(e)) => println!("line {}: {}", n + 1, e),
+        (true, Ok(solved)) => println!(
+            "{{\"input\":{},\"solved\":true,\"solution\":{},\"error\":null}}",
+            json_string(s),
+            json_string(&solved.to_line())
+        ),
+        (true, Err(e)) => println!(
+            "{{\"input\":{},\"solved\":false,\"solution\":null,\"error\":{}}}",
+            json_string(s),
+            json_string(&e.to_string())
+        ),
+    }
+    if let Some(stuck) = stuck {
+        print!("{}", stuck);
+    }
+    if let Some(took) = took.filter(|_| options.time) {
+        println!("line {}: {:.2?}", n + 1, took);
+        timings.record(n, took);
+    }
+}
 
-        let width = self.cells.iter().map(|c| c.len() + 1).max().unwrap();
-        let sep = "-".repeat(3 * width);
-        for i in 0..9 {
-            if i == 3 || i == 6 {
-                writeln!(f, "{}+-{}+-{}", sep, sep, sep)?;
+/// Solves the puzzles of `input`, read as [`read_euler`] reads them,
+/// printing each one's header before its solution, or before why it has
+/// none.
+fn solve_euler(input: impl BufRead, path: &str) -> ExitCode {
+    let puzzles = match read_euler(input, SudokuBoard::standard()) {
+        Ok(puzzles) => puzzles,
+        Err(e) => {
+            eprintln!("{}: {}", path, e);
+            return ExitCode::FAILURE;
+        }
+    };
+    for (header, sudoku) in puzzles {
+        println!("{}", header);
+        match sudoku
+            .map_err(SolveError::from)
+            .and_then(|sudoku| sudoku.try_solve())
+        {
+            Ok(solved) => println!("{}", solved),
+            Err(e) => println!("{}", e),
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+/// Solves the single puzzle of the SadMan `.sdk` file at `path`.
+fn solve_sdk(path: &str) -> ExitCode {
+    let sdk = match fs::read_to_string(path) {
+        Ok(sdk) => sdk,
+        Err(e) => {
+            eprintln!("{}: {}", path, e);
+            return ExitCode::FAILURE;
+        }
+    };
+    match Sudoku::from_sdk(&sdk, SudokuBoard::standard())
+        .map_err(SolveError::from)
+        .and_then(|sudoku| sudoku.try_solve())
+    {
+        Ok(solved) => println!("{}", solved),
+        Err(e) => println!("{}", e),
+    }
+    ExitCode::SUCCESS
+}
+
+/// Opens the file at `path` for reading lines, decompressing it on the fly
+/// if its name ends in `.gz`.
+fn open(path: &str) -> io::Result<Box<dyn BufRead>> {
+    let file = File::open(path)?;
+    if !path.ends_with(".gz") {
+        return Ok(Box::new(BufReader::new(file)));
+    }
+    #[cfg(feature = "gzip")]
+    {
+        Ok(Box::new(BufReader::new(flate2::read::MultiGzDecoder::new(
+            file,
+        ))))
+    }
+    #[cfg(not(feature = "gzip"))]
+    {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "reading .gz files needs the gzip feature",
+        ))
+    }
+}
+
+fn main() -> ExitCode {
+    let mut args = env::args().skip(1).peekable();
+    if args.next_if(|a| a == "generate").is_some() {
+        let Some(request) = generate::Request::parse(args) else {
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        };
+        return match request.run(io::stdout().lock()) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("stdout: {}", e);
+                ExitCode::FAILURE
+            }
+        };
+    }
+    let Some(options) = Options::parse(args) else {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    };
+    if options.interactive {
+        return match repl::run(io::stdin().lock(), io::stdout().lock()) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("stdin: {}", e);
+                ExitCode::FAILURE
+            }
+        };
+    }
+    if let Some(path) = options.path.as_deref().filter(|p| p.ends_with(".sdk")) {
+        return solve_sdk(path);
+    }
+    let input: Box<dyn BufRead> = match &options.path {
+        None => Box::new(io::stdin().lock()),
+        Some(path) => match open(path) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("{}: {}", path, e);
+                return ExitCode::FAILURE;
             }
-            for j in 0..9 {
-                if j == 3 || j == 6 {
-                    write!(f, "| ")?;
+        },
+    };
+
+    if options.euler {
+        return solve_euler(input, options.path.as_deref().unwrap_or("stdin"));
+    }
+
+    let mut progress = Progress {
+        on: options.progress,
+        done: 0,
+        shown: 0,
+    };
+    let plain = [
+        options.grid,
+        options.json,
+        options.check,
+        options.time,
+        options.dump_on_fail,
+        options.color,
+        options.stats,
+    ];
+    if plain.iter().all(|o| !o) && options.all.is_none() {
+        let path = options.path.as_deref().unwrap_or("stdin");
+        let solved = solve_stream_with_progress(
+            input,
+            io::stdout().lock(),
+            SudokuBoard::standard(),
+            PROGRESS_EVERY,
+            |done| {
+                progress.done = done;
+                progress.show();
+            },
+        );
+        progress.finish();
+        return match solved {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("{}: {}", path, e);
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    let mut puzzles = Puzzles {
+        grid: options.grid,
+        current: None,
+    };
+    let mut timings = Timings::default();
+    let mut corpus = Corpus::default();
+    let mut handle = |n: usize, s: &str| {
+        if options.stats {
+            corpus.add(s);
+        } else {
+            solve(&options, &mut timings, n, s);
+        }
+    };
+    for (n, line) in input.lines().enumerate() {
+        match line {
+            Ok(s) => {
+                if let Some((n, s)) = puzzles.push(n, s) {
+                    handle(n, &s);
+                    progress.tick();
                 }
-                write!(
-                    f,
-                    "{:width$}",
-                    bitvector_to_str(&self.cells[i * 9 + j]),
-                    width = width
-                )?;
             }
-            writeln!(f, "")?;
+            Err(e) => {
+                progress.finish();
+                let path = options.path.as_deref().unwrap_or("stdin");
+                eprintln!("{}: line {}: {}", path, n + 1, e);
+                return ExitCode::FAILURE;
+            }
         }
-        writeln!(f, "")
     }
-}
-
-fn main() {
-    let board = SudokuBoard::new();
-    let lines = io::stdin().lines();
-    for line in lines {
-        let s = line.unwrap();
-        let sudoku = Sudoku::from(&s, &board);
-        let solved = sudoku.solve();
-        println!("{}", solved.expect("No solution"));
+    if let Some((n, s)) = puzzles.finish() {
+        handle(n, &s);
+        progress.tick();
+    }
+    progress.finish();
+    if let Some(summary) = timings.summary() {
+        println!("{}", summary);
+    }
+    if options.stats {
+        match options.json {
+            true => println!("{}", corpus.json()),
+            false => print!("{}", corpus.table()),
+        }
     }
+    ExitCode::SUCCESS
 }
 
 #[cfg(test)]
 mod tests {
-    use super::SudokuBoard;
-    use std::collections::HashSet;
+    use super::{json_string, Options, Puzzles, Timings, ALL_CAP};
+    use std::time::Duration;
+    #[cfg(feature = "gzip")]
+    use std::{env, fs, io::BufRead, io::Write};
+
+    const PUZZLE: &str =
+        "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+
+    fn gather(grid: bool, lines: &[&str]) -> Vec<(usize, String)> {
+        let mut puzzles = Puzzles {
+            grid,
+            current: None,
+        };
+        let mut out: Vec<_> = lines
+            .iter()
+            .enumerate()
+            .filter_map(|(n, l)| puzzles.push(n, l.to_string()))
+            .collect();
+        out.extend(puzzles.finish());
+        out
+    }
 
     #[test]
-    fn init_works() {
-        let s = SudokuBoard::new();
-        assert_eq!(s.units.len(), 27);
-        assert!((0..81).map(|i| s.units_for[i].len()).all(|n| n == 3));
-        assert!((0..81).map(|i| s.neighbors[i].len()).all(|n| n == 20));
-        assert_eq!(s.units_for[19], vec![2, 10, 18]);
-        assert_eq!(s.units[18], vec![0, 1, 2, 9, 10, 11, 18, 19, 20]);
+    fn blank_lines_are_not_puzzles() {
+        let lines = ["", PUZZLE, "   ", "\t", PUZZLE, ""];
+        let puzzles = gather(false, &lines);
         assert_eq!(
-            s.neighbors[19],
-            HashSet::from_iter(vec![
-                18, 20, 21, 22, 23, 24, 25, 26, 01, 10, 28, 37, 46, 55, 64, 73, 00, 02, 09, 11,
-            ])
+            puzzles,
+            vec![(1, PUZZLE.to_string()), (4, PUZZLE.to_string())]
         );
+
+        let rows: Vec<&str> = (0..9).map(|r| &PUZZLE[r * 9..r * 9 + 9]).collect();
+        let mut lines = vec![""];
+        lines.extend(&rows);
+        lines.extend(["", ""]);
+        lines.extend(&rows[..3]);
+        let puzzles = gather(true, &lines);
+        assert_eq!(puzzles.len(), 2);
+        assert_eq!(puzzles[0], (1, PUZZLE.to_string()));
+        assert_eq!(puzzles[1], (12, PUZZLE[..27].to_string()));
+    }
+
+    #[test]
+    fn all_takes_an_optional_cap() {
+        let parse = |args: &[&str]| {
+            Options::parse(args.iter().map(|a| a.to_string())).map(|o| (o.all, o.path))
+        };
+        assert_eq!(parse(&[]), Some((None, None)));
+        assert_eq!(parse(&["--all"]), Some((Some(ALL_CAP), None)));
+        assert_eq!(
+            parse(&["--all=3", "in.txt"]),
+            Some((Some(3), Some("in.txt".to_string())))
+        );
+        assert_eq!(parse(&["--all=0"]), None);
+        assert_eq!(parse(&["--all=x"]), None);
+        assert_eq!(parse(&["--all", "--json"]), None);
+        assert_eq!(parse(&["--interactive", "--all"]), None);
+        assert_eq!(parse(&["--check", "--all"]), None);
+        assert!(parse(&["--check", "--grid", "in.txt"]).is_some());
+        assert_eq!(parse(&["--interactive", "in.txt"]), None);
+        assert!(parse(&["--time", "--grid"]).is_some());
+        assert_eq!(parse(&["--time", "--json"]), None);
+        assert!(parse(&["--dump-on-fail", "--time", "--grid"]).is_some());
+        assert_eq!(parse(&["--dump-on-fail", "--check"]), None);
+        assert_eq!(parse(&["--dump-on-fail", "--interactive"]), None);
+        assert!(parse(&["--progress", "--all", "in.txt"]).is_some());
+        assert_eq!(parse(&["--progress", "--interactive"]), None);
+        assert!(parse(&["--format", "euler", "p096.txt"]).is_some());
+        assert_eq!(parse(&["--format", "sdm"]), None);
+        assert_eq!(parse(&["--format"]), None);
+        assert_eq!(parse(&["--format", "euler", "--grid"]), None);
+        assert!(parse(&["--color", "--all", "--grid"]).is_some());
+        assert_eq!(parse(&["--color", "--json"]), None);
+        assert_eq!(parse(&["--color", "--format", "euler"]), None);
+        assert!(parse(&["stats", "--json", "--grid", "in.txt"]).is_some());
+        assert_eq!(parse(&["stats", "--time"]), None);
+        assert_eq!(parse(&["stats", "--interactive"]), None);
+        assert_eq!(
+            parse(&["--json", "stats"]).map(|o| o.1),
+            Some(Some("stats".to_string()))
+        );
+    }
+
+    #[test]
+    fn timings_sum_up_solves() {
+        let mut timings = Timings::default();
+        assert_eq!(timings.summary(), None);
+        for (n, ms) in [(0, 2), (1, 7), (3, 3)] {
+            timings.record(n, Duration::from_millis(ms));
+        }
+        assert_eq!(
+            timings.summary().unwrap(),
+            "3 puzzles in 12.00ms: 4.00ms on average, 7.00ms at most, on line 2"
+        );
+    }
+
+    #[test]
+    fn json_strings_are_escaped() {
+        assert_eq!(json_string("4.....8.5"), "\"4.....8.5\"");
+        assert_eq!(json_string("a\"b\\c\td\u{1}"), "\"a\\\"b\\\\c\\td\\u0001\"");
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn gzipped_files_are_decompressed() {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        for _ in 0..100 {
+            writeln!(encoder, "{}", PUZZLE).unwrap();
+        }
+        let gz = encoder.finish().unwrap();
+
+        let path = env::temp_dir().join(format!("sudoku-solver-{}.txt.gz", std::process::id()));
+        let path = path.to_str().unwrap();
+        fs::write(path, &gz).unwrap();
+        let lines: Vec<String> = super::open(path)
+            .unwrap()
+            .lines()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(lines, vec![PUZZLE.to_string(); 100]);
+
+        fs::write(path, &gz[..gz.len() / 2]).unwrap();
+        let read: Result<Vec<String>, _> = super::open(path).unwrap().lines().collect();
+        fs::remove_file(path).unwrap();
+        assert!(read.is_err());
     }
 }
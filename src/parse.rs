@@ -0,0 +1,57 @@
+/// The characters that stand for an empty cell in a puzzle string.
+///
+/// Digits of the board (`1`-`9`, plus `A`-`G` on a 16x16 one) are always
+/// givens, whatever the set says. Characters that
+/// are neither givens nor empties are skipped as separators.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmptyChars(Vec<char>);
+
+impl EmptyChars {
+    pub fn new(chars: &str) -> EmptyChars {
+        EmptyChars(chars.chars().collect())
+    }
+
+    #[inline]
+    pub fn contains(&self, c: char) -> bool {
+        self.0.contains(&c)
+    }
+}
+
+impl Default for EmptyChars {
+    /// `0` and `.`, the two most common conventions.
+    fn default() -> Self {
+        EmptyChars::new("0.")
+    }
+}
+
+/// The digit `c` stands for on a board of the given side, if any. Past 9,
+/// digits continue with letters in either case.
+#[inline]
+pub(crate) fn digit_of(c: char, side: usize) -> Option<usize> {
+    c.to_digit(side as u32 + 1)
+        .filter(|v| *v > 0)
+        .map(|v| v as usize)
+}
+
+/// The character for digit `v`, as [`Sudoku`](crate::Sudoku) writes it in
+/// lines and in its `Display`: `1` to `9`, then capital letters past 9.
+#[inline]
+pub fn char_of(v: usize) -> char {
+    char::from_digit(v as u32, 36).unwrap().to_ascii_uppercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{char_of, digit_of};
+
+    #[test]
+    fn digits_follow_the_side() {
+        assert_eq!(digit_of('9', 9), Some(9));
+        assert_eq!(digit_of('0', 9), None);
+        assert_eq!(digit_of('5', 4), None);
+        assert_eq!(digit_of('g', 16), Some(16));
+        assert_eq!(digit_of('G', 16), Some(16));
+        assert_eq!(digit_of('A', 9), None);
+        assert!((1..=16).all(|v| digit_of(char_of(v), 16) == Some(v)));
+    }
+}
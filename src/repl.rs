@@ -0,0 +1,257 @@
+//! The `--interactive` mode: a puzzle read once, then worked through one
+//! command at a time.
+
+use std::io::{self, BufRead, Write};
+use sudoku_solver::{Grid, Hint, HintKind, MoveError, Sudoku, SudokuBoard};
+
+const COMMANDS: &str = "commands:
+  show              print the grid
+  hint              name a cell that can be filled in by logic alone
+  place R C V       put V at row R, column C
+  candidates R C    list the digits left for row R, column C
+  undo              take back the last place
+  solve             print the solution
+  quit              leave";
+
+/// The digits on the player's grid, the givens and their own, with the
+/// cells they filled in, last one last. Propagation happens out of sight,
+/// on a [`Sudoku`] made from the grid: the grid itself only ever holds
+/// digits somebody wrote, so a move is never taken out of their hands.
+struct Game {
+    digits: Vec<u8>,
+    placed: Vec<usize>,
+}
+
+impl Game {
+    /// The grid with its candidates worked out. Every move is checked
+    /// before it's made, so the grid always has one.
+    fn sudoku(&self) -> Sudoku<'static> {
+        Grid::from(&self.digits[..])
+            .to_sudoku(SudokuBoard::standard())
+            .unwrap()
+    }
+
+    /// The grid the way [`Sudoku::to_pretty`] draws it.
+    fn show(&self) -> String {
+        let board = SudokuBoard::standard();
+        let (n, side) = (board.box_size(), board.side());
+        let border = format!("+{}", format!("{}+", "-".repeat(n)).repeat(n));
+        let mut lines = Vec::new();
+        for (i, row) in self.digits.chunks(side).enumerate() {
+            if i % n == 0 {
+                lines.push(border.clone());
+            }
+            let mut line = String::new();
+            for (j, d) in row.iter().enumerate() {
+                if j % n == 0 {
+                    line.push('|');
+                }
+                line.push(match d {
+                    0 => '.',
+                    d => char::from(b'0' + d),
+                });
+            }
+            line.push('|');
+            lines.push(line);
+        }
+        lines.push(border);
+        lines.join("\n")
+    }
+
+    /// Writes `value` in `cell` if the grid allows it: the move is played
+    /// on the grid's [`Sudoku`] first.
+    fn place(&mut self, cell: usize, value: usize) -> Result<(), MoveError> {
+        if self.digits[cell] != 0 {
+            return Err(MoveError::AlreadyFilled { cell });
+        }
+        let mut sudoku = self.sudoku();
+        // Propagation may have decided the cell already.
+        match sudoku.value(cell) {
+            Some(v) if v == value => {}
+            Some(_) => return Err(MoveError::NotACandidate { cell, value }),
+            None => sudoku.play(cell, value)?,
+        }
+        self.digits[cell] = value as u8;
+        self.placed.push(cell);
+        Ok(())
+    }
+
+    /// Takes back the last move, if there's one.
+    fn undo(&mut self) -> bool {
+        match self.placed.pop() {
+            Some(cell) => {
+                self.digits[cell] = 0;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// A [`Hint`] for the digits written so far, spelled out.
+    fn hint(&self) -> String {
+        let board = SudokuBoard::standard();
+        let Ok(Some(Hint { cell, value, kind })) = Grid::from(&self.digits[..]).hint(board) else {
+            return "no hint: every open cell needs deeper reasoning or a guess".to_string();
+        };
+        let side = board.side();
+        let why = match kind {
+            HintKind::NakedSingle => "every other digit is taken by its peers".to_string(),
+            HintKind::HiddenSingle { unit } => {
+                let kind = ["row", "column", "box"].get(unit / side).unwrap_or(&"unit");
+                format!("no other cell of {} {} can hold it", kind, unit % side + 1)
+            }
+        };
+        format!(
+            "row {}, column {} is {}: {}",
+            board.row_of(cell) + 1,
+            board.col_of(cell) + 1,
+            value,
+            why
+        )
+    }
+}
+
+/// Reads a puzzle from `input`, asking again until one parses, and then
+/// runs commands from the lines after it until `quit` or the end of the
+/// input. Rows and columns count from 1, as players count them.
+pub fn run(mut input: impl BufRead, mut out: impl Write) -> io::Result<()> {
+    let board = SudokuBoard::standard();
+    let mut line = String::new();
+    let mut game = loop {
+        write!(out, "puzzle> ")?;
+        out.flush()?;
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        // Givens that run into a contradiction are turned down here, so
+        // the game never holds a grid without candidates.
+        match Grid::parse(&line, board).and_then(|grid| grid.to_sudoku(board).map(|_| grid)) {
+            Ok(grid) => {
+                break Game {
+                    digits: grid.digits().to_vec(),
+                    placed: Vec::new(),
+                }
+            }
+            Err(e) => writeln!(out, "{}", e)?,
+        }
+    };
+    writeln!(out, "{}", game.show())?;
+    loop {
+        write!(out, "> ")?;
+        out.flush()?;
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let reply = match words[..] {
+            [] => continue,
+            ["quit"] => return Ok(()),
+            ["show"] => game.show(),
+            ["hint"] => game.hint(),
+            ["undo"] if game.undo() => game.show(),
+            ["undo"] => "nothing to undo".to_string(),
+            ["solve"] => match game.sudoku().try_solve() {
+                Ok(solved) => solved.to_pretty().trim_end().to_string(),
+                Err(e) => e.to_string(),
+            },
+            ["candidates", r, c] => match cell(board, r, c) {
+                Some(k) => game
+                    .sudoku()
+                    .candidates(k)
+                    .iter()
+                    .map(usize::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                None => COMMANDS.to_string(),
+            },
+            ["place", r, c, v] => match (cell(board, r, c), v.parse()) {
+                (Some(k), Ok(v)) if (1..=board.side()).contains(&v) => match game.place(k, v) {
+                    Ok(()) => game.show(),
+                    Err(MoveError::AlreadyFilled { .. }) => {
+                        format!("row {}, column {} is already filled", r, c)
+                    }
+                    Err(MoveError::NotACandidate { .. }) => {
+                        format!("{} is ruled out for row {}, column {}", v, r, c)
+                    }
+                    Err(MoveError::Contradiction { .. }) => {
+                        format!("{} at row {}, column {} contradicts the grid", v, r, c)
+                    }
+                },
+                _ => COMMANDS.to_string(),
+            },
+            _ => COMMANDS.to_string(),
+        };
+        writeln!(out, "{}", reply)?;
+    }
+}
+
+/// The cell at row `r` and column `c`, counting from 1, if it's on `board`.
+fn cell(board: &SudokuBoard, r: &str, c: &str) -> Option<usize> {
+    let side = board.side();
+    let (r, c): (usize, usize) = (r.parse().ok()?, c.parse().ok()?);
+    ((1..=side).contains(&r) && (1..=side).contains(&c)).then(|| (r - 1) * side + c - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::run;
+    use std::io::Cursor;
+
+    const PUZZLE: &str =
+        "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+
+    /// The replies to `commands` on `puzzle`, past the grid printed first.
+    fn session(puzzle: &str, commands: &str) -> Vec<String> {
+        let mut out = Vec::new();
+        run(Cursor::new(format!("{}\n{}", puzzle, commands)), &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let mut replies: Vec<String> = out
+            .split("> ")
+            .skip(2)
+            .map(|r| r.trim_end().to_string())
+            .collect();
+        // The prompt the input ended on, or `quit` answered.
+        assert_eq!(replies.pop().as_deref(), Some(""));
+        replies
+    }
+
+    #[test]
+    fn commands_answer_in_turn() {
+        let commands = "candidates 1 2\nplace 1 2 4\nplace 1 2 1\nundo\nundo\nfly\nquit\nshow\n";
+        let replies = session(PUZZLE, commands);
+        assert_eq!(replies.len(), 6);
+        assert_eq!(replies[0], "1 6 7 9");
+        assert_eq!(replies[1], "4 is ruled out for row 1, column 2");
+        assert!(replies[2].starts_with("+---+---+---+\n|41."));
+        assert!(replies[3].starts_with("+---+---+---+\n|4.."));
+        assert_eq!(replies[4], "nothing to undo");
+        assert!(replies[5].starts_with("commands:"));
+    }
+
+    #[test]
+    fn hint_and_solve() {
+        let replies = session(PUZZLE, "hint\nsolve\n");
+        assert_eq!(
+            replies[0],
+            "row 6, column 2 is 4: no other cell of column 2 can hold it"
+        );
+        assert!(replies[1].starts_with("+---+---+---+\n|417|369|825|"));
+
+        let almost =
+            "..7369825632158947958724316825437169791586432346912758289643571573291684164875293";
+        let replies = session(almost, "hint\nplace 1 2 4\nplace 1 2 1\nplace 1 2 1\n");
+        assert_eq!(
+            replies[0],
+            "row 1, column 1 is 4: every other digit is taken by its peers"
+        );
+        // Parsing solved the grid, but only the player's digits are shown.
+        assert_eq!(replies[1], "4 is ruled out for row 1, column 2");
+        assert!(replies[2].starts_with("+---+---+---+\n|.17|369|825|"));
+        assert_eq!(replies[3], "row 1, column 2 is already filled");
+    }
+}
@@ -0,0 +1,92 @@
+//! The file formats of SadMan Sudoku: `.sdk` for a single puzzle, with
+//! metadata, and `.sdm` for a collection of them.
+
+use crate::board::SudokuBoard;
+use crate::error::ParseError;
+use crate::sudoku::Sudoku;
+use std::io::{self, BufRead};
+
+/// Whether `line` is metadata, such as `#A` for the author or `#D` for a
+/// description, rather than part of a puzzle.
+fn is_metadata(line: &str) -> bool {
+    line.trim_start().starts_with('#')
+}
+
+impl<'a> Sudoku<'a> {
+    /// Parses a `.sdk` file: the puzzle laid out one row per line, after
+    /// any number of `#` metadata lines, which are skipped. What's left is
+    /// read like [`Sudoku::from_grid_lines`] reads its lines, `.` and `0`
+    /// being empty cells.
+    pub fn from_sdk(s: &str, board: &'a SudokuBoard) -> Result<Sudoku<'a>, ParseError> {
+        let rows: Vec<&str> = s.lines().filter(|l| !is_metadata(l)).collect();
+        Sudoku::from_grid_lines(&rows, board)
+    }
+}
+
+/// Reads a `.sdm` file: one puzzle per line, as [`Sudoku::from`] takes
+/// them. Blank lines are skipped, and so are `#` metadata lines, so a
+/// result stands for each other line, in order.
+///
+/// Only failing to read stops the reader, the error saying on which line
+/// it happened: a malformed puzzle is just an `Err` among the results.
+pub fn read_sdm<'a>(
+    reader: impl BufRead,
+    board: &'a SudokuBoard,
+) -> io::Result<Vec<Result<Sudoku<'a>, ParseError>>> {
+    let mut puzzles = Vec::new();
+    for (n, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| io::Error::new(e.kind(), format!("line {}: {}", n + 1, e)))?;
+        if !line.trim().is_empty() && !is_metadata(&line) {
+            puzzles.push(Sudoku::from(&line, board));
+        }
+    }
+    Ok(puzzles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::read_sdm;
+    use crate::board::SudokuBoard;
+    use crate::error::ParseError;
+    use crate::sudoku::Sudoku;
+    use std::io::Cursor;
+
+    const PUZZLE: &str =
+        "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+
+    #[test]
+    fn sdk_skips_metadata() {
+        let board = SudokuBoard::new();
+        let mut sdk = String::from("#A Arto Inkala\n#D 17 clues and 3 digits: #1\n#B 2012\n");
+        for r in 0..9 {
+            sdk.push_str(&PUZZLE[r * 9..r * 9 + 9].replace('.', "0"));
+            sdk.push_str("\r\n");
+        }
+        let sudoku = Sudoku::from_sdk(&sdk, &board).unwrap();
+        assert_eq!(
+            sudoku.to_line(),
+            Sudoku::from(PUZZLE, &board).unwrap().to_line()
+        );
+        assert_eq!(sudoku.given_count(), 17);
+    }
+
+    #[test]
+    fn sdm_has_a_result_per_puzzle() {
+        let board = SudokuBoard::new();
+        let sdm = format!("{}\n\n#C a comment\n1234\n{}\n", PUZZLE, PUZZLE);
+        let puzzles = read_sdm(Cursor::new(sdm), &board).unwrap();
+        assert_eq!(puzzles.len(), 3);
+        assert!(puzzles[0].is_ok() && puzzles[2].is_ok());
+        assert_eq!(
+            puzzles[1].as_ref().err(),
+            Some(&ParseError::BadLength {
+                expected: 81,
+                got: 4
+            })
+        );
+
+        let bad = Cursor::new(vec![b'.', b'\n', 0xff, b'\n']);
+        let e = read_sdm(bad, &board).err().unwrap();
+        assert!(e.to_string().starts_with("line 2: "));
+    }
+}
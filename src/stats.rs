@@ -0,0 +1,102 @@
+use crate::journal::Journal;
+
+/// How much work a search took.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SolveStats {
+    /// Values tried at branch points. Zero when propagation alone solves
+    /// the puzzle.
+    pub guesses: usize,
+    /// Candidates removed by propagation during the search, including those
+    /// later rolled back.
+    pub eliminations: usize,
+    /// Cells narrowed down to a single digit during the search, by
+    /// propagation or by a guess, including those later rolled back.
+    pub assignments: usize,
+    /// Guesses that led nowhere and were rolled back.
+    pub backtracks: usize,
+}
+
+impl SolveStats {
+    /// Maps these counts onto a [`Difficulty`]. The bands are picked so that
+    /// `benches/data/top95.txt` spreads over most of them: from Trivial to
+    /// Hard with every technique on, with Diabolical left for searches
+    /// without them.
+    pub fn difficulty(&self) -> Difficulty {
+        match self.guesses {
+            0 => Difficulty::Trivial,
+            1..=9 => Difficulty::Easy,
+            10..=49 => Difficulty::Medium,
+            50..=199 => Difficulty::Hard,
+            _ => Difficulty::Diabolical,
+        }
+    }
+}
+
+impl Journal for SolveStats {
+    #[inline]
+    fn removed(&mut self, _: usize, _: usize) {
+        self.eliminations += 1;
+    }
+
+    #[inline]
+    fn assigned(&mut self, _: usize, _: usize) {
+        self.assignments += 1;
+    }
+
+    #[inline]
+    fn guessed(&mut self, _: usize, _: usize) {
+        self.guesses += 1;
+    }
+
+    #[inline]
+    fn backtracked(&mut self, _: usize, _: usize) {
+        self.backtracks += 1;
+    }
+}
+
+/// A rough grade of how hard a puzzle is for the solver. Trivial puzzles
+/// fall to constraint propagation alone, the rest need increasingly many
+/// guesses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Difficulty {
+    Trivial,
+    Easy,
+    Medium,
+    Hard,
+    Diabolical,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Difficulty;
+    use crate::board::SudokuBoard;
+    use crate::sudoku::{Sudoku, Techniques};
+
+    #[test]
+    fn propagation_only_is_trivial() {
+        let board = SudokuBoard::new();
+        let s = "..3.2.6..9..3.5..1..18.64....81.29..7.......8..67.82....26.95..8..2.3..9..5.1.3..";
+        let sudoku = Sudoku::from(s, &board).unwrap();
+        let (solved, stats) = sudoku.solve_stats();
+        assert!(solved.unwrap().is_solved());
+        assert_eq!((stats.guesses, stats.backtracks), (0, 0));
+        assert_eq!(sudoku.difficulty(), Difficulty::Trivial);
+    }
+
+    #[test]
+    fn guesses_raise_difficulty() {
+        let board = SudokuBoard::new();
+        let s = "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+        let mut sudoku = Sudoku::from(s, &board).unwrap();
+        // Pointing alone cracks this one, so take it out to force guessing.
+        sudoku.set_techniques(Techniques::NONE);
+        let (solved, stats) = sudoku.solve_stats();
+        assert_eq!(solved.unwrap().to_line(), sudoku.solve().unwrap().to_line());
+        assert!(stats.guesses > 0 && stats.eliminations > 0);
+        // Every guess but those on the way to the solution was taken back,
+        // and every open cell was narrowed down at least once.
+        assert!(stats.backtracks < stats.guesses);
+        assert!(stats.assignments >= 81 - sudoku.filled_count());
+        assert!(stats.difficulty() > Difficulty::Trivial);
+    }
+}
@@ -0,0 +1,1805 @@
+use crate::board::SudokuBoard;
+use crate::candidates::{Candidates, Cell};
+use crate::error::{Conflict, GridError, ParseError, SolveError};
+use crate::grid::Grid;
+use crate::hint::{Hint, HintKind};
+use crate::journal::{Budget, Journal, Trail};
+use crate::parse::{char_of, EmptyChars};
+use crate::stats::{Difficulty, SolveStats};
+use crate::trace::{Placements, Step};
+use core::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+mod backdoor;
+mod builder;
+mod constraints;
+mod generate;
+mod killer;
+mod marks;
+mod order;
+mod play;
+mod render;
+mod samurai;
+mod techniques;
+mod thermo;
+mod unavoidable;
+
+pub use builder::SudokuBuilder;
+pub use constraints::Constraint;
+pub use generate::{
+    generate, generate_symmetric, generate_symmetric_with_difficulty, generate_with_difficulty,
+};
+pub use killer::Cage;
+pub use marks::Mark;
+pub use order::ValueOrder;
+pub use samurai::SamuraiSudoku;
+pub use techniques::{LogicalResult, Technique, Techniques};
+pub use thermo::Thermo;
+
+/// A Sudoku grid: the set of remaining candidates for each cell of a
+/// [`SudokuBoard`], each kept as a [`Cell`], [`Candidates`] unless
+/// [`with_cells`](Sudoku::with_cells) says otherwise.
+///
+/// A grid borrows its board, so many grids can share one. A
+/// `Sudoku<'static>` is the owned form: [`str::parse`] builds one on a
+/// lazily constructed standard board, which is handy when there's no board
+/// around to borrow. Both forms behave identically.
+#[derive(Clone)]
+pub struct Sudoku<'a, C: Cell = Candidates> {
+    board: &'a SudokuBoard,
+    cells: Vec<C>,
+    constraints: Vec<Constraint>,
+    /// The cells given when the grid was parsed, one bit each: boards stop
+    /// at 16x16, so 256 bits cover them all.
+    givens: [u64; 4],
+    /// How many cells the givens determined once placed, propagation
+    /// included.
+    clues: usize,
+    /// Scratch stack for [`Sudoku::propagate`].
+    pending: Vec<Task>,
+    techniques: Techniques,
+    /// How the search orders the candidates it tries, see
+    /// [`Sudoku::solve_with`].
+    order: ValueOrder,
+    /// The cells as they were before each move [`Sudoku::play`] made, most
+    /// recent last.
+    history: Vec<Vec<C>>,
+}
+
+/// A step of [`Sudoku::propagate`] still to take.
+#[derive(Clone, Copy)]
+enum Task {
+    /// Rule out a value from a cell.
+    Remove(usize, usize),
+    /// Check where a unit can still put a value it just lost a place for.
+    Unit(usize, usize),
+}
+
+impl<'a> Sudoku<'a> {
+    /// A grid with no givens, every digit still a candidate for every cell:
+    /// the starting point to place givens on, with
+    /// [`assign_all`](Sudoku::assign_all) or [`play`](Sudoku::play).
+    ///
+    /// Any filled grid completes it, so [`solve`](Sudoku::solve) returns
+    /// some valid one, the one the search happens to reach first.
+    pub fn empty(board: &'a SudokuBoard) -> Sudoku<'a> {
+        Sudoku {
+            board,
+            cells: vec![Candidates::all(board.side()); board.cell_count()],
+            constraints: Vec::new(),
+            givens: [0; 4],
+            clues: 0,
+            pending: Vec::new(),
+            techniques: Techniques::default(),
+            order: ValueOrder::default(),
+            history: Vec::new(),
+        }
+    }
+
+    /// Parses a puzzle, one cell per character in row-major order. Digits
+    /// `1`-`9` are givens, `0` and `.` are empty cells, and anything else
+    /// is ignored. Boards larger than 9x9 continue their digits with the
+    /// letters `A`-`G`.
+    ///
+    /// Fails if the input does not describe exactly one character per cell,
+    /// or if some given contradicts the ones before it. An empty or blank
+    /// line is therefore an error, while a line of empty cells only is a
+    /// valid grid, if one with a great many solutions.
+    pub fn from(s: &str, board: &'a SudokuBoard) -> Result<Sudoku<'a>, ParseError> {
+        Sudoku::from_with(s, board, &EmptyChars::default())
+    }
+
+    /// Like [`Sudoku::from`], but with `empty` deciding which characters
+    /// are empty cells.
+    pub fn from_with(
+        s: &str,
+        board: &'a SudokuBoard,
+        empty: &EmptyChars,
+    ) -> Result<Sudoku<'a>, ParseError> {
+        Sudoku::from_grid(&Grid::parse_with(s, board, empty)?, board)
+    }
+
+    /// Parses a puzzle laid out over several lines, such as one row per
+    /// line with `-+-` rules between the boxes. The lines are read in
+    /// order as if they were one, so the rules and any spaces are ignored
+    /// like every other character that isn't a cell.
+    pub fn from_grid_lines(
+        lines: &[&str],
+        board: &'a SudokuBoard,
+    ) -> Result<Sudoku<'a>, ParseError> {
+        Sudoku::from(&lines.concat(), board)
+    }
+
+    /// Places `bytes`, one digit per cell in row-major order and `0` for
+    /// empty cells, on `board`, failing like [`from_grid`](Sudoku::from_grid)
+    /// fails. The inverse of [`to_bytes`](Sudoku::to_bytes) on a solution,
+    /// this is for puzzles that arrive as numbers rather than text.
+    pub fn from_bytes(bytes: &[u8], board: &'a SudokuBoard) -> Result<Sudoku<'a>, ParseError> {
+        Sudoku::from_grid(&Grid::from(bytes), board)
+    }
+
+    /// Places the digits of `grid` on `board`, `0` meaning an empty cell.
+    ///
+    /// Fails if the grid doesn't have one digit per cell of the board, if
+    /// some digit is too large for it, or if some digit contradicts the
+    /// ones before it.
+    pub fn from_grid(grid: &Grid, board: &'a SudokuBoard) -> Result<Sudoku<'a>, ParseError> {
+        grid.check(board)?;
+        let digits = grid.digits().iter().map(|v| *v as usize).enumerate();
+        let mut sudoku = Sudoku::empty(board);
+        sudoku
+            .assign_givens(digits.filter(|(_, v)| *v != 0))
+            .map_err(|(cell, _)| ParseError::Contradiction { cell })?;
+        Ok(sudoku)
+    }
+
+    /// Checks that no unit holds the same digit twice among the determined
+    /// cells, as [`Grid::validate`] does for the [`Grid`] of this one.
+    ///
+    /// Propagation never leaves two peers with the same digit on its own,
+    /// but a grid left partway through a contradiction may.
+    pub fn validate(&self) -> Result<(), Conflict> {
+        match Grid::from(self).validate(self.board) {
+            Ok(()) => Ok(()),
+            Err(GridError::Conflict(conflict)) => Err(conflict),
+            Err(GridError::Malformed(_)) => unreachable!("a grid always fits its own board"),
+        }
+    }
+}
+
+impl<'a, C: Cell> Sudoku<'a, C> {
+    /// The board this grid is laid out on.
+    #[inline]
+    pub fn board(&self) -> &'a SudokuBoard {
+        self.board
+    }
+
+    /// This grid with every cell's candidates kept as a `D`, everything
+    /// else as it is, to run the solver on another [`Cell`] than the one
+    /// it was built with.
+    pub fn with_cells<D: Cell>(&self) -> Sudoku<'a, D> {
+        let convert = |cells: &[C]| -> Vec<D> {
+            cells
+                .iter()
+                .map(|c| {
+                    c.iter().fold(D::default(), |mut d, v| {
+                        d.insert(v);
+                        d
+                    })
+                })
+                .collect()
+        };
+        Sudoku {
+            board: self.board,
+            cells: convert(&self.cells),
+            constraints: self.constraints.clone(),
+            givens: self.givens,
+            clues: self.clues,
+            pending: Vec::new(),
+            techniques: self.techniques,
+            order: self.order,
+            history: self.history.iter().map(|cells| convert(cells)).collect(),
+        }
+    }
+
+    /// The techniques the search applies before each guess.
+    #[inline]
+    pub fn techniques(&self) -> Techniques {
+        self.techniques
+    }
+
+    /// Changes the techniques the search applies before each guess. Naked
+    /// and hidden singles are always applied, whatever the set says.
+    pub fn set_techniques(&mut self, techniques: Techniques) {
+        self.techniques = techniques;
+    }
+
+    /// Empties the grid in place: every cell gets back every digit, and the
+    /// givens and moves are forgotten. The board, the constraints and the
+    /// techniques stay, so a variant puzzle goes back to its starting
+    /// point, its givens aside. No memory is allocated.
+    pub fn reset(&mut self) {
+        self.cells.fill(C::all(self.board.side()));
+        self.givens = [0; 4];
+        self.clues = 0;
+        self.history.clear();
+    }
+
+    /// Places each `(cell, value)` of `givens` in turn, as givens, the way
+    /// [`Sudoku::from`] places the digits it reads.
+    ///
+    /// Fails on the first pair whose value has been ruled out for its cell,
+    /// by the givens before it or by this grid's own digits, returning that
+    /// pair. A value of `0` or past the largest digit is ruled out
+    /// everywhere. The pairs before it stay placed, along with whatever
+    /// they propagated, so the grid is best dropped after a failure.
+    ///
+    /// # Panics
+    ///
+    /// If some cell isn't on the board.
+    pub fn assign_all(&mut self, givens: &[(usize, usize)]) -> Result<(), (usize, usize)> {
+        self.assign_givens(givens.iter().copied())
+    }
+
+    fn assign_givens(
+        &mut self,
+        givens: impl IntoIterator<Item = (usize, usize)>,
+    ) -> Result<(), (usize, usize)> {
+        for (k, v) in givens {
+            assert!(k < self.cells.len(), "cell out of the board");
+            self.givens[k / 64] |= 1 << (k % 64);
+            if !self.assign(k, v) {
+                return Err((k, v));
+            }
+        }
+        self.clues = self.filled_count();
+        Ok(())
+    }
+
+    /// Places `value` in `cell`, ruling out its other candidates, with the
+    /// full propagation of [`eliminate`](Sudoku::eliminate) behind each.
+    /// Returns `false` if that leads to a contradiction: some cell left
+    /// without candidates, or some digit without a place in some unit.
+    /// Placing a digit already ruled out for the cell is a contradiction
+    /// too, as is a value of `0` or past the largest digit.
+    ///
+    /// Unlike [`play`](Sudoku::play), this checks nothing beforehand and
+    /// keeps no history: after a contradiction the grid is left partway
+    /// through propagation, and is best dropped, so drive it on a clone
+    /// when a contradiction should be recoverable. The grid's techniques
+    /// and constraints aren't applied either, only the singles rules.
+    ///
+    /// # Panics
+    ///
+    /// If `cell` isn't on the board.
+    #[must_use = "a `false` means the grid contradicts itself"]
+    pub fn assign(&mut self, cell: usize, value: usize) -> bool {
+        self.assign_with(cell, value, &mut ())
+    }
+
+    /// Rules `value` out for `cell`, following it up as propagation does:
+    /// a cell left with a single candidate clears it out of its peers, and
+    /// a unit left with a single place for a digit puts it there, every
+    /// consequence in turn until none is left. Returns `false` if that
+    /// leads to a contradiction, with the grid left partway through as for
+    /// [`assign`](Sudoku::assign). Ruling out a digit that's gone already
+    /// does nothing.
+    ///
+    /// # Panics
+    ///
+    /// If `cell` isn't on the board.
+    #[must_use = "a `false` means the grid contradicts itself"]
+    pub fn eliminate(&mut self, cell: usize, value: usize) -> bool {
+        self.eliminate_with(cell, value, &mut ())
+    }
+
+    /// Rules out every candidate of `cell` but those in `allowed`, with the
+    /// propagation of [`eliminate`](Sudoku::eliminate) behind each, for
+    /// clues that only narrow a cell down, such as a cell known to be even.
+    /// Returns `false` if that leads to a contradiction, with the grid left
+    /// as for [`assign`](Sudoku::assign). Allowing a single digit places
+    /// it, and allowing none is a contradiction, while digits in `allowed`
+    /// that aren't candidates, or aren't digits of the board, are ignored.
+    ///
+    /// # Panics
+    ///
+    /// If `cell` isn't on the board.
+    #[must_use = "a `false` means the grid contradicts itself"]
+    pub fn restrict(&mut self, cell: usize, allowed: &[usize]) -> bool {
+        let side = self.board.side();
+        self.run(&mut (), |pending| {
+            pending.extend(
+                (1..=side)
+                    .rev()
+                    .filter(|v| !allowed.contains(v))
+                    .map(|v| Task::Remove(cell, v)),
+            )
+        })
+    }
+
+    #[must_use = "a `false` means the grid contradicts itself"]
+    fn assign_with<J: Journal>(&mut self, k: usize, v: usize, j: &mut J) -> bool {
+        let side = self.board.side();
+        self.run(j, |pending| {
+            pending.extend(
+                (1..=side)
+                    .rev()
+                    .filter(|i| *i != v)
+                    .map(|i| Task::Remove(k, i)),
+            )
+        })
+    }
+
+    #[must_use = "a `false` means the grid contradicts itself"]
+    fn eliminate_with<J: Journal>(&mut self, k: usize, v: usize, j: &mut J) -> bool {
+        self.propagate(&[(k, v)], j)
+    }
+
+    /// `k` is down to a single candidate, which none of its peers can keep.
+    #[must_use = "a `false` means the grid contradicts itself"]
+    fn naked_single<J: Journal>(&mut self, k: usize, j: &mut J) -> bool {
+        let val = self.uniq_val(k);
+        j.assigned(k, val);
+        let board = self.board;
+        self.run(j, |pending| {
+            pending.extend(board.neighbors(k).iter().map(|n| Task::Remove(*n, val)))
+        })
+    }
+
+    /// Rules out each `(cell, value)` of `seeds`, along with everything that
+    /// follows from it, returning whether the grid is still consistent.
+    ///
+    /// Each removal is handled as the recursive formulation would: a cell
+    /// left with one candidate clears it out of its peers, while a cell left
+    /// with several checks whether its units still have more than one place
+    /// for the removed value, one unit at a time, each once the previous
+    /// unit's follow-ups are done. Follow-ups go on an explicit stack rather
+    /// than the call stack, so the depth of a cascade is bounded by memory
+    /// alone, whatever the size of the board.
+    ///
+    /// A `false` is final. Propagation stops at the first cell left without
+    /// candidates, or digit left without a place, with the rest of the
+    /// stack abandoned, so the grid is left partway through and no longer
+    /// means anything: every caller has to stop there too, and either
+    /// drop the grid or put back what was removed, which the search does
+    /// by rolling back its [`Trail`] to the mark taken before the guess.
+    /// Going on from such a grid, as if the removals made so far were
+    /// sound, is the one mistake to avoid, hence the `#[must_use]` on
+    /// everything returning this verdict.
+    #[must_use = "a `false` means the grid contradicts itself"]
+    fn propagate<J: Journal>(&mut self, seeds: &[(usize, usize)], j: &mut J) -> bool {
+        self.run(j, |pending| {
+            pending.extend(seeds.iter().rev().map(|&(k, v)| Task::Remove(k, v)))
+        })
+    }
+
+    /// Runs [`propagate`](Sudoku::propagate) over the removals `seed` puts
+    /// on the stack. The stack is kept between calls to spare allocations.
+    #[must_use = "a `false` means the grid contradicts itself"]
+    fn run<J, F>(&mut self, j: &mut J, seed: F) -> bool
+    where
+        J: Journal,
+        F: FnOnce(&mut Vec<Task>),
+    {
+        let mut pending = std::mem::take(&mut self.pending);
+        pending.clear();
+        seed(&mut pending);
+        let consistent = self.drain(&mut pending, j);
+        self.pending = pending;
+        consistent
+    }
+
+    #[must_use = "a `false` means the grid contradicts itself"]
+    fn drain<J: Journal>(&mut self, pending: &mut Vec<Task>, j: &mut J) -> bool {
+        let board = self.board;
+        while let Some(task) = pending.pop() {
+            let at = pending.len();
+            match task {
+                Task::Remove(k, v) => {
+                    if !self.cells[k].contains(v) {
+                        continue;
+                    }
+                    self.cells[k].remove(v);
+                    j.removed(k, v);
+                    match self.cells[k].len() {
+                        0 => return false,
+                        1 => {
+                            let val = self.uniq_val(k);
+                            j.assigned(k, val);
+                            pending
+                                .extend(board.neighbors(k).iter().map(|n| Task::Remove(*n, val)));
+                        }
+                        _ => pending.extend(board.units_for(k).iter().map(|u| Task::Unit(*u, v))),
+                    }
+                }
+                Task::Unit(u, v) => {
+                    let mut ps = board
+                        .cells_in_unit(u)
+                        .iter()
+                        .filter(|p| self.cells[**p].contains(v));
+                    match (ps.next(), ps.next()) {
+                        (None, _) => return false,
+                        (Some(&p), None) => pending.extend(
+                            self.cells[p]
+                                .iter()
+                                .filter(|i| *i != v)
+                                .map(|i| Task::Remove(p, i)),
+                        ),
+                        _ => {}
+                    }
+                }
+            }
+            pending[at..].reverse();
+        }
+        true
+    }
+
+    /// Puts back every candidate removed since `mark`.
+    fn roll_back<J: Journal>(&mut self, trail: &mut Trail<J>, mark: usize) {
+        for (k, v) in trail.rewind(mark) {
+            self.cells[k].insert(v);
+        }
+    }
+
+    #[inline]
+    fn uniq_val(&self, k: usize) -> usize {
+        self.cells[k].single().unwrap()
+    }
+
+    /// The grid as a single line of one character per cell in row-major
+    /// order: the digit for every determined cell and `.` for the rest.
+    /// This is the inverse of [`Sudoku::from`].
+    pub fn to_line(&self) -> String {
+        let mut line = String::with_capacity(self.cells.len());
+        self.write_line(&mut line)
+            .expect("writing to a String can't fail");
+        line
+    }
+
+    /// Writes [`to_line`](Sudoku::to_line) to `w` a character at a time,
+    /// with no `String` in between, for printing solutions by the million.
+    /// [`Display`](fmt::Display) lays out every candidate of every cell,
+    /// and allocates a string per cell to do it.
+    pub fn write_line(&self, w: &mut impl fmt::Write) -> fmt::Result {
+        for c in &self.cells {
+            w.write_char(c.single().map_or('.', char_of))?;
+        }
+        Ok(())
+    }
+
+    /// The digits of a solved 9x9 grid in row-major order, for consumers
+    /// that want numbers rather than text. `None` while some cell still
+    /// has several candidates, and on boards of any other size.
+    pub fn to_bytes(&self) -> Option<[u8; 81]> {
+        let mut bytes = [0; 81];
+        if self.cells.len() != bytes.len() {
+            return None;
+        }
+        for (b, c) in bytes.iter_mut().zip(&self.cells) {
+            *b = c.single()? as u8;
+        }
+        Some(bytes)
+    }
+
+    /// The number of digits still possible in each cell, in row-major
+    /// order. Determined cells count `1`.
+    ///
+    /// One count per cell of the board, hence a `Vec` rather than the
+    /// `[u8; 81]` of a standard grid: unlike [`to_bytes`](Sudoku::to_bytes),
+    /// there's an answer on every board, down to 4x4 and up to 16x16.
+    pub fn candidate_counts(&self) -> Vec<u8> {
+        self.cells.iter().map(|c| c.len() as u8).collect()
+    }
+
+    /// The digits still possible in `cell`, in ascending order.
+    pub fn candidates(&self, cell: usize) -> Vec<usize> {
+        self.cells[cell].iter().collect()
+    }
+
+    /// The digit of `cell` if it's down to one, `None` while it still has
+    /// several candidates.
+    #[inline]
+    pub fn value(&self, cell: usize) -> Option<usize> {
+        self.cells[cell].single()
+    }
+
+    /// The determined cells where this grid and `other` disagree, as
+    /// `(cell, value here, value in other)`, in cell order. Cells with
+    /// several candidates left in either grid are not compared.
+    ///
+    /// # Panics
+    ///
+    /// If the two grids don't have the same number of cells.
+    pub fn diff(&self, other: &Sudoku<'_, C>) -> Vec<(usize, usize, usize)> {
+        assert_eq!(
+            self.cells.len(),
+            other.cells.len(),
+            "grids of different sizes"
+        );
+        self.cells
+            .iter()
+            .zip(&other.cells)
+            .enumerate()
+            .filter_map(|(k, (a, b))| match (a.single(), b.single()) {
+                (Some(v), Some(w)) if v != w => Some((k, v, w)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The number of cells with a single candidate right after the givens
+    /// were parsed: the givens, and whatever propagating them determined.
+    /// Unlike [`filled_count`](Sudoku::filled_count), it stays the same
+    /// however far the search and moves go.
+    #[inline]
+    pub fn clue_count(&self) -> usize {
+        self.clues
+    }
+
+    /// The number of givens the grid was parsed from, those
+    /// [`is_given`](Sudoku::is_given) reports.
+    #[inline]
+    pub fn given_count(&self) -> usize {
+        self.givens.iter().map(|g| g.count_ones() as usize).sum()
+    }
+
+    /// Whether `cell` was given when the grid was parsed, rather than
+    /// found by propagation, the search or a move.
+    ///
+    /// # Panics
+    ///
+    /// If `cell` isn't on the board.
+    #[inline]
+    pub fn is_given(&self, cell: usize) -> bool {
+        assert!(cell < self.cells.len(), "cell out of the board");
+        self.givens[cell / 64] & 1 << (cell % 64) != 0
+    }
+
+    /// The givens the grid was parsed from, as `(cell, digit)` in cell
+    /// order: the puzzle again, in the form
+    /// [`assign_all`](Sudoku::assign_all) takes, even from a solution.
+    pub fn givens(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.cells.len())
+            .filter(|k| self.is_given(*k))
+            .filter_map(|k| Some((k, self.value(k)?)))
+    }
+
+    /// The next open cell that can be filled in from where the grid
+    /// stands, givens, moves and propagation included, if any.
+    ///
+    /// Works from the candidates left rather than from the digits alone,
+    /// as [`Grid::hint`] does, and never points at a cell that already
+    /// holds a digit. A cell down to one candidate holds it already, so the
+    /// hint is always a hidden single propagation left behind: the first
+    /// unit, in order, with a single open place left for some digit. `None`
+    /// means no open cell can be filled in without deeper reasoning or a
+    /// guess.
+    pub fn hint(&self) -> Option<Hint> {
+        let board = self.board;
+        (0..board.unit_count()).find_map(|unit| {
+            (1..=board.side()).find_map(|value| {
+                let cells = board.cells_in_unit(unit);
+                let mut places = cells.iter().filter(|k| self.cells[**k].contains(value));
+                match (places.next(), places.next()) {
+                    (Some(&cell), None) if self.cells[cell].len() > 1 => Some(Hint {
+                        cell,
+                        value,
+                        kind: HintKind::HiddenSingle { unit },
+                    }),
+                    _ => None,
+                }
+            })
+        })
+    }
+
+    /// The number of cells narrowed down to a single digit so far.
+    pub fn filled_count(&self) -> usize {
+        self.cells.iter().filter(|c| c.len() == 1).count()
+    }
+
+    /// Whether every cell has been narrowed down to a single digit.
+    #[inline]
+    pub fn is_solved(&self) -> bool {
+        self.cells.iter().all(|k| k.len() == 1)
+    }
+
+    /// Whether this grid is a solution: every cell narrowed down to a
+    /// single digit, no two peers holding the same one, and every
+    /// constraint met.
+    ///
+    /// Unlike [`is_solved`](Sudoku::is_solved), which trusts propagation to
+    /// have kept the digits apart, this checks every peer and constraint
+    /// again, as a grid from elsewhere deserves.
+    pub fn is_valid_solution(&self) -> bool {
+        self.is_solved()
+            && (0..self.cells.len()).all(|k| {
+                let v = self.cells[k];
+                self.board.neighbors(k).iter().all(|p| self.cells[*p] != v)
+            })
+            && self
+                .constraints
+                .iter()
+                .all(|c| c.holds(|k| self.uniq_val(k)))
+    }
+
+    /// The cell to branch on: one with the fewest candidates left, ties
+    /// going to the one with the most undetermined peers, whose guess has
+    /// the most cells to propagate to.
+    fn select_cell(&self) -> usize {
+        // No cell left to branch on has fewer than 2 candidates, so the
+        // first one with 2 ends the scan.
+        let mut fewest = usize::MAX;
+        for n in self.cells.iter().map(|c| c.len()).filter(|n| *n > 1) {
+            if n < fewest {
+                fewest = n;
+                if n == 2 {
+                    break;
+                }
+            }
+        }
+        let open_peers = |k: usize| {
+            self.board
+                .neighbors(k)
+                .iter()
+                .filter(|p| self.cells[**p].len() > 1)
+                .count()
+        };
+        (0..self.cells.len())
+            .filter(|k| self.cells[*k].len() == fewest)
+            .max_by_key(|k| (open_peers(*k), std::cmp::Reverse(*k)))
+            .unwrap()
+    }
+
+    /// Depth-first search over the completions of this grid, calling
+    /// `found` on each until it returns `true`. Each node first applies the
+    /// grid's [`Techniques`], then branches on the cell with the fewest
+    /// candidates. Branches are explored in place and rolled back through
+    /// `trail`, so no grid is cloned along the way. Returns whether `found`
+    /// asked to stop, or the journal ran out of guesses.
+    fn search<J, F>(&mut self, trail: &mut Trail<J>, found: &mut F) -> bool
+    where
+        J: Journal,
+        F: FnMut(&Sudoku<'a, C>) -> bool,
+    {
+        if self.reduce(trail).is_err() {
+            return false;
+        }
+        if self.is_solved() {
+            return found(self);
+        }
+        let k = self.select_cell();
+        let (values, count) = self.ordered_values(k);
+        for &v in &values[..count] {
+            if trail.exhausted() {
+                return true;
+            }
+            let mark = trail.mark();
+            trail.guessed(k, v);
+            if self.assign_with(k, v, trail) && self.search(trail, found) {
+                return true;
+            }
+            self.roll_back(trail, mark);
+            trail.backtracked(k, v);
+        }
+        false
+    }
+
+    /// Searches for a completion of this grid, returning the first one found.
+    ///
+    /// Like every solution the search hands back, it keeps the givens of
+    /// this grid, so [`is_given`](Sudoku::is_given) and
+    /// [`givens`](Sudoku::givens) tell its clues from the digits found
+    /// without the puzzle at hand.
+    pub fn solve(&self) -> Option<Sudoku<'a, C>> {
+        let mut solution = self.clone();
+        self.solve_into(&mut solution).then_some(solution)
+    }
+
+    /// Like [`solve`](Sudoku::solve), but searching in `out` rather than in
+    /// a fresh grid: `out` is overwritten with this grid, reusing its
+    /// buffers, and the search runs in it. Returns whether a solution was
+    /// found, in which case `out` holds it. Otherwise `out` holds this grid
+    /// with propagation applied, and no solution.
+    ///
+    /// Passing the same `out` to every call of a loop spares allocating a
+    /// grid per puzzle. `out` may have been built on any board.
+    pub fn solve_into(&self, out: &mut Sudoku<'a, C>) -> bool {
+        out.board = self.board;
+        out.cells.clone_from(&self.cells);
+        out.constraints.clone_from(&self.constraints);
+        out.givens = self.givens;
+        out.clues = self.clues;
+        out.techniques = self.techniques;
+        out.order = self.order;
+        out.history.clear();
+        out.search(&mut Trail::new(()), &mut |_| true)
+    }
+
+    /// Counts the completions of this grid, walking the same search tree as
+    /// [`solve`](Sudoku::solve) but stopping as soon as `cap` are found.
+    pub fn count_solutions(&self, cap: usize) -> usize {
+        let mut count = 0;
+        if cap > 0 {
+            self.clone().search(&mut Trail::new(()), &mut |_| {
+                count += 1;
+                count == cap
+            });
+        }
+        count
+    }
+
+    /// Solves this grid like [`solve`](Sudoku::solve), also recording every
+    /// deduction and guess the search makes, in order, so it can be
+    /// replayed. The trace starts from this grid, so it doesn't include the
+    /// propagation of the givens.
+    pub fn solve_traced(&self) -> (Option<Sudoku<'a, C>>, Vec<Step>) {
+        let mut trail = Trail::new(Vec::new());
+        let mut solution = None;
+        self.clone().search(&mut trail, &mut |s| {
+            solution = Some(s.clone());
+            true
+        });
+        (solution, trail.journal)
+    }
+
+    /// The `(cell, value)` placements leading from this grid to its first
+    /// solution, in the order the search made them: propagation and guesses
+    /// alike, leaving out everything undone by backtracking. Cells already
+    /// determined in this grid aren't placed again, so each undetermined
+    /// cell appears exactly once. `None` if there's no solution.
+    pub fn solution_order(&self) -> Option<Vec<(usize, usize)>> {
+        let mut trail = Trail::new(Placements::default());
+        self.clone()
+            .search(&mut trail, &mut |_| true)
+            .then_some(trail.journal.order)
+    }
+
+    /// Solves this grid like [`solve`](Sudoku::solve), also counting the
+    /// work the search took. [`solve`](Sudoku::solve) itself counts
+    /// nothing, so it pays nothing for this.
+    pub fn solve_stats(&self) -> (Option<Sudoku<'a, C>>, SolveStats) {
+        let mut trail = Trail::new(SolveStats::default());
+        let mut solution = self.clone();
+        let found = solution.search(&mut trail, &mut |_| true);
+        (found.then_some(solution), trail.journal)
+    }
+
+    /// Grades this grid by the effort [`solve_stats`](Sudoku::solve_stats)
+    /// measures.
+    pub fn difficulty(&self) -> Difficulty {
+        self.solve_stats().1.difficulty()
+    }
+
+    /// Lazily enumerates every completion of this grid, in the order
+    /// [`solve`](Sudoku::solve) would find them.
+    pub fn solutions(&self) -> impl Iterator<Item = Sudoku<'a, C>> {
+        let mut pending = vec![self.clone()];
+        // A random order draws from one sequence as the branches are
+        // explored, as it does along the search, so it's kept out here.
+        let mut order = self.order;
+        std::iter::from_fn(move || {
+            while let Some(mut s) = pending.pop() {
+                if s.reduce(&mut ()).is_err() {
+                    continue;
+                }
+                if s.is_solved() {
+                    return Some(s);
+                }
+                // Push in reverse so the first candidate to try is explored
+                // first.
+                let k = s.select_cell();
+                s.order = order;
+                let (values, count) = s.ordered_values(k);
+                order = s.order;
+                for &v in values[..count].iter().rev() {
+                    let mut t = s.clone();
+                    if t.assign(k, v) {
+                        pending.push(t);
+                    }
+                }
+            }
+            None
+        })
+    }
+
+    /// Whether this grid has exactly one completion. The search stops as
+    /// soon as a second one turns up.
+    pub fn has_unique_solution(&self) -> bool {
+        self.count_solutions(2) == 1
+    }
+
+    /// Like [`solve`](Sudoku::solve), but reports a missing solution as
+    /// [`SolveError::NoSolution`].
+    pub fn try_solve(&self) -> Result<Sudoku<'a, C>, SolveError> {
+        self.solve().ok_or(SolveError::NoSolution)
+    }
+
+    /// This grid as far as propagation and its techniques take it without
+    /// guessing: where the search would make its first guess, on a grid
+    /// they don't solve. On a grid they find unsolvable, they stop at the
+    /// first contradiction, which leaves some cell without candidates, or
+    /// some digit without a place in some unit.
+    pub fn propagated(&self) -> Sudoku<'a, C> {
+        let mut propagated = self.clone();
+        propagated.history.clear();
+        let _ = propagated.reduce(&mut ());
+        propagated
+    }
+
+    /// Like [`try_solve`](Sudoku::try_solve), but giving up with
+    /// [`SolveError::BudgetExceeded`] rather than make more than
+    /// `max_guesses` guesses, which bounds the time a hostile puzzle can
+    /// take. Puzzles that propagation alone solves need no budget at all.
+    pub fn solve_with_budget(&self, max_guesses: usize) -> Result<Sudoku<'a, C>, SolveError> {
+        let mut trail = Trail::new(Budget { left: max_guesses });
+        let mut solution = self.clone();
+        match solution.search(&mut trail, &mut |_| true) {
+            true if solution.is_solved() => Ok(solution),
+            true => Err(SolveError::BudgetExceeded),
+            false => Err(SolveError::NoSolution),
+        }
+    }
+}
+
+impl FromStr for Sudoku<'static> {
+    type Err = ParseError;
+
+    /// Parses a puzzle on the shared standard board, following the same
+    /// rules as [`Sudoku::from`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Sudoku::from(s, SudokuBoard::standard())
+    }
+}
+
+impl TryFrom<&[u8]> for Sudoku<'static> {
+    type Error = ParseError;
+
+    /// Places the digits on the shared standard board, `0` meaning an empty
+    /// cell, following the same rules as [`Sudoku::from_grid`].
+    fn try_from(digits: &[u8]) -> Result<Self, Self::Error> {
+        Sudoku::from_grid(&Grid::from(digits), SudokuBoard::standard())
+    }
+}
+
+impl TryFrom<[u8; 81]> for Sudoku<'static> {
+    type Error = ParseError;
+
+    /// Like the conversion from `&[u8]`, with the length fixed by the type.
+    fn try_from(digits: [u8; 81]) -> Result<Self, Self::Error> {
+        Sudoku::try_from(&digits[..])
+    }
+}
+
+impl<C: Cell> fmt::Display for Sudoku<'_, C> {
+    /// Every cell's candidates, padded to a common width: that of the
+    /// longest candidate list plus a space, so columns and box separators
+    /// line up whatever the mix of solved and unsolved cells.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let n = self.board.box_size();
+        let side = self.board.side();
+        let cells: Vec<String> = self
+            .cells
+            .iter()
+            .map(|c| c.iter().map(char_of).collect())
+            .collect();
+        let width = cells.iter().map(|c| c.chars().count()).max().unwrap() + 1;
+        let sep = vec!["-".repeat(n * width); n].join("+-");
+        for i in 0..side {
+            if i > 0 && i % n == 0 {
+                writeln!(f, "{}", sep)?;
+            }
+            for j in 0..side {
+                if j > 0 && j % n == 0 {
+                    write!(f, "| ")?;
+                }
+                write!(f, "{:width$}", cells[i * side + j], width = width)?;
+            }
+            writeln!(f)?;
+        }
+        writeln!(f)
+    }
+}
+
+/// Two grids are equal when every cell has the same candidates in both,
+/// whatever boards, constraints or techniques they were built with: two
+/// solutions are equal when they hold the same digits.
+impl<C: Cell> PartialEq for Sudoku<'_, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cells == other.cells
+    }
+}
+
+impl<C: Cell> Eq for Sudoku<'_, C> {}
+
+impl<C: Cell> Hash for Sudoku<'_, C> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.cells.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Sudoku, Techniques, ValueOrder};
+    use crate::board::SudokuBoard;
+    use crate::candidates::{Candidates, Cell};
+    use crate::error::{Conflict, MoveError, ParseError, SolveError};
+    use crate::journal::Trail;
+    use crate::parse::EmptyChars;
+    use crate::trace::Step;
+    use std::collections::HashSet;
+    use std::fs;
+
+    /// The recursive propagation [`Sudoku::propagate`] replaced, kept to
+    /// check the two agree.
+    mod recursive {
+        use super::{Cell, Sudoku};
+
+        pub fn assign(s: &mut Sudoku, k: usize, v: usize) -> bool {
+            (1..=s.board.side())
+                .filter(|i| *i != v)
+                .all(|i| eliminate(s, k, i))
+        }
+
+        fn eliminate(s: &mut Sudoku, k: usize, v: usize) -> bool {
+            if !s.cells[k].contains(v) {
+                return true;
+            }
+            s.cells[k].remove(v);
+            match s.cells[k].len() {
+                0 => false,
+                1 => {
+                    let val = s.uniq_val(k);
+                    s.board.neighbors(k).iter().all(|n| eliminate(s, *n, val))
+                }
+                _ => s.board.units_for(k).iter().all(|u| {
+                    let unit = s.board.cells_in_unit(*u);
+                    let mut ps = unit.iter().filter(|p| s.cells[**p].contains(v));
+                    match (ps.next(), ps.next()) {
+                        (None, _) => false,
+                        (Some(&p), None) => assign(s, p, v),
+                        _ => true,
+                    }
+                }),
+            }
+        }
+    }
+
+    const PUZZLE: &str =
+        "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+    // Propagation alone doesn't catch this one, only the search does.
+    const NO_SOLUTION: &str =
+        "49....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+
+    #[test]
+    fn propagate_matches_recursion() {
+        let board = SudokuBoard::new();
+        let top95 = fs::read_to_string("benches/data/top95.txt").unwrap();
+        let hardest = fs::read_to_string("benches/data/hardest.txt").unwrap();
+        for line in top95.lines().chain(hardest.lines()) {
+            let iterative = Sudoku::from(line, &board).unwrap();
+            let mut reference = Sudoku::empty(&board);
+            for (k, c) in line.chars().enumerate() {
+                if let Some(v) = c.to_digit(10).filter(|v| *v != 0) {
+                    assert!(recursive::assign(&mut reference, k, v as usize));
+                }
+            }
+            assert_eq!(iterative.cells, reference.cells, "{}", line);
+
+            // And for every first guess, whether it holds up or not.
+            if iterative.is_solved() {
+                continue;
+            }
+            let k = iterative.select_cell();
+            for v in iterative.cells[k].iter() {
+                let (mut a, mut b) = (iterative.clone(), reference.clone());
+                let consistent = a.assign(k, v);
+                assert_eq!(consistent, recursive::assign(&mut b, k, v), "{}", line);
+                if consistent {
+                    assert_eq!(a.cells, b.cells, "{}", line);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn from_rejects_bad_input() {
+        let board = SudokuBoard::new();
+        for blank in ["", "   ", "\n"] {
+            assert_eq!(
+                Sudoku::from(blank, &board).err(),
+                Some(ParseError::BadLength {
+                    expected: 81,
+                    got: 0
+                })
+            );
+        }
+        assert_eq!(
+            Sudoku::from("123", &board).err(),
+            Some(ParseError::BadLength {
+                expected: 81,
+                got: 3
+            })
+        );
+        let dup = format!("11{}", ".".repeat(79));
+        assert_eq!(
+            Sudoku::from(&dup, &board).err(),
+            Some(ParseError::Contradiction { cell: 1 })
+        );
+    }
+
+    #[test]
+    fn from_with_custom_empties() {
+        let board = SudokuBoard::new();
+        let dashed = PUZZLE.replace('.', "-");
+        assert_eq!(
+            Sudoku::from(&dashed, &board).err(),
+            Some(ParseError::BadLength {
+                expected: 81,
+                got: 17
+            })
+        );
+        let sudoku = Sudoku::from_with(&dashed, &board, &EmptyChars::new("-")).unwrap();
+        assert_eq!(
+            sudoku.to_line(),
+            Sudoku::from(PUZZLE, &board).unwrap().to_line()
+        );
+
+        let spaced = PUZZLE.replace('.', " ");
+        let sudoku = Sudoku::from_with(&spaced, &board, &EmptyChars::new(" ")).unwrap();
+        assert_eq!(
+            sudoku.to_line(),
+            Sudoku::from(PUZZLE, &board).unwrap().to_line()
+        );
+        assert_eq!(
+            Sudoku::from_with(&spaced[1..], &board, &EmptyChars::new(" ")).err(),
+            Some(ParseError::BadLength {
+                expected: 81,
+                got: 80
+            })
+        );
+    }
+
+    #[test]
+    fn propagation_stops_where_guessing_starts() {
+        let board = SudokuBoard::new();
+        let s = "48.3............71.2.......7.5....6....2..8.............1.76...3.....4......5....";
+        let sudoku = Sudoku::from(s, &board).unwrap();
+        let propagated = sudoku.propagated();
+        assert!(!propagated.is_solved());
+        assert!(propagated.filled_count() >= sudoku.filled_count());
+        assert_eq!(propagated.propagated().to_line(), propagated.to_line());
+        assert_eq!(
+            propagated.solve().unwrap().to_line(),
+            sudoku.solve().unwrap().to_line()
+        );
+
+        let stuck = Sudoku::from(NO_SOLUTION, &board).unwrap().propagated();
+        assert!(!stuck.is_solved());
+        assert!(stuck.solve().is_none());
+    }
+
+    #[test]
+    fn solve_into_reuses_the_output() {
+        let board = SudokuBoard::new();
+        let small = SudokuBoard::with_box_size(2);
+        let mut out = Sudoku::from("1.....2..3.....4", &small).unwrap();
+        let top95 = fs::read_to_string("benches/data/top95.txt").unwrap();
+        for line in top95.lines().take(10) {
+            let sudoku = Sudoku::from(line, &board).unwrap();
+            assert!(sudoku.solve_into(&mut out));
+            assert_eq!(out.to_line(), sudoku.solve().unwrap().to_line());
+            assert_eq!(out.clue_count(), sudoku.clue_count());
+        }
+        let sudoku = Sudoku::from(NO_SOLUTION, &board).unwrap();
+        assert!(!sudoku.solve_into(&mut out));
+        assert!(!out.is_solved());
+    }
+
+    #[test]
+    fn solution_order_places_each_open_cell_once() {
+        let board = SudokuBoard::new();
+        let sudoku = Sudoku::from(PUZZLE, &board).unwrap();
+        let order = sudoku.solution_order().unwrap();
+        assert_eq!(order.len(), 81 - sudoku.filled_count());
+
+        let mut replay = sudoku.clone();
+        for (cell, value) in order {
+            assert_eq!(replay.value(cell), None);
+            assert!(replay.candidates(cell).contains(&value));
+            replay.cells[cell] = Candidates::default();
+            replay.cells[cell].insert(value);
+        }
+        assert_eq!(replay.to_line(), sudoku.solve().unwrap().to_line());
+
+        let none = Sudoku::from(NO_SOLUTION, &board).unwrap();
+        assert_eq!(none.solution_order(), None);
+    }
+
+    #[test]
+    fn try_solve_reports_no_solution() {
+        let board = SudokuBoard::new();
+        let sudoku = Sudoku::from(NO_SOLUTION, &board).unwrap();
+        assert_eq!(sudoku.try_solve().err(), Some(SolveError::NoSolution));
+    }
+
+    #[test]
+    fn is_valid_solution_rechecks_units() {
+        let board = SudokuBoard::new();
+        let sudoku = Sudoku::from(PUZZLE, &board).unwrap();
+        assert!(!sudoku.is_valid_solution());
+        let mut solved = sudoku.solve().unwrap();
+        assert!(solved.is_valid_solution());
+
+        // Every cell still has a single digit, but the first row holds the
+        // second one's digit twice.
+        solved.cells[1] = solved.cells[0];
+        assert!(solved.is_solved());
+        assert!(!solved.is_valid_solution());
+
+        // Swapping two cells of a row keeps the row whole but breaks their
+        // columns.
+        let mut solved = sudoku.solve().unwrap();
+        solved.cells.swap(0, 1);
+        assert!(!solved.is_valid_solution());
+    }
+
+    #[test]
+    fn reset_empties_in_place() {
+        let board = SudokuBoard::new();
+        let mut sudoku = Sudoku::from(PUZZLE, &board).unwrap().solve().unwrap();
+        let cells = sudoku.cells.as_ptr();
+        sudoku.reset();
+        assert_eq!(sudoku.cells.as_ptr(), cells);
+        assert!(std::ptr::eq(sudoku.board(), &board));
+        assert_eq!(
+            (
+                sudoku.filled_count(),
+                sudoku.clue_count(),
+                sudoku.given_count()
+            ),
+            (0, 0, 0)
+        );
+        assert_eq!(sudoku.to_line(), Sudoku::empty(&board).to_line());
+        assert!(sudoku.solve().is_some());
+    }
+
+    #[test]
+    fn solve_with_budget_caps_guesses() {
+        let board = SudokuBoard::new();
+        let mut sudoku = Sudoku::from(PUZZLE, &board).unwrap();
+        sudoku.set_techniques(Techniques::NONE);
+        let guesses = sudoku.solve_stats().1.guesses;
+        assert!(guesses > 1);
+        assert_eq!(
+            sudoku.solve_with_budget(guesses).unwrap().to_line(),
+            sudoku.solve().unwrap().to_line()
+        );
+        assert_eq!(
+            sudoku.solve_with_budget(guesses - 1).err(),
+            Some(SolveError::BudgetExceeded)
+        );
+        assert_eq!(
+            sudoku.solve_with_budget(0).err(),
+            Some(SolveError::BudgetExceeded)
+        );
+
+        let no_solution = Sudoku::from(NO_SOLUTION, &board).unwrap();
+        assert_eq!(
+            no_solution.solve_with_budget(usize::MAX).err(),
+            Some(SolveError::NoSolution)
+        );
+    }
+
+    #[test]
+    fn count_solutions_edge_cases() {
+        let board = SudokuBoard::new();
+        let sudoku = Sudoku::from(PUZZLE, &board).unwrap();
+        assert_eq!(sudoku.count_solutions(100), 1);
+        assert_eq!(sudoku.solve().unwrap().count_solutions(100), 1);
+        assert_eq!(
+            Sudoku::from(NO_SOLUTION, &board)
+                .unwrap()
+                .count_solutions(100),
+            0
+        );
+        let empty = Sudoku::empty(&board);
+        assert_eq!(empty.count_solutions(10), 10);
+        assert_eq!(empty.count_solutions(0), 0);
+    }
+
+    #[test]
+    fn has_unique_solution_works() {
+        let board = SudokuBoard::new();
+        assert!(Sudoku::from(PUZZLE, &board).unwrap().has_unique_solution());
+        assert!(!Sudoku::from(NO_SOLUTION, &board)
+            .unwrap()
+            .has_unique_solution());
+        let near_empty = format!("123{}", ".".repeat(78));
+        assert!(!Sudoku::from(&near_empty, &board)
+            .unwrap()
+            .has_unique_solution());
+        assert!(!Sudoku::empty(&board).has_unique_solution());
+    }
+
+    fn assert_rectangular(s: &Sudoku) {
+        let text = s.to_string();
+        let lines: Vec<&str> = text.lines().filter(|l| !l.is_empty()).collect();
+        let side = s.board().side();
+        let n = s.board().box_size();
+        assert_eq!(lines.len(), side + n - 1);
+        let len = lines[0].chars().count();
+        assert!(lines.iter().all(|l| l.chars().count() == len), "{}", text);
+
+        // Box separators sit in the same columns on every line.
+        let rule: Vec<usize> = lines[n].match_indices('+').map(|(i, _)| i).collect();
+        assert_eq!(rule.len(), n - 1);
+        for l in lines.iter().filter(|l| !l.contains('+')) {
+            let bars: Vec<usize> = l.match_indices('|').map(|(i, _)| i).collect();
+            assert_eq!(bars, rule);
+        }
+    }
+
+    #[test]
+    fn display_is_rectangular() {
+        let board = SudokuBoard::new();
+        let sudoku = Sudoku::from(PUZZLE, &board).unwrap();
+        assert_rectangular(&sudoku);
+        assert_rectangular(&sudoku.solve().unwrap());
+        assert_rectangular(&Sudoku::empty(&board));
+        assert_rectangular(&Sudoku::from(&format!("123{}", ".".repeat(78)), &board).unwrap());
+
+        let small = SudokuBoard::with_box_size(2);
+        assert_rectangular(&Sudoku::from("1.....2..3.....4", &small).unwrap());
+        let large = SudokuBoard::with_box_size(4);
+        assert_rectangular(&Sudoku::empty(&large));
+    }
+
+    #[test]
+    fn from_grid_lines_skips_rules() {
+        let board = SudokuBoard::new();
+        let lines = [
+            "4 . . | . . . | 8 . 5",
+            ". 3 . | . . . | . . .",
+            ". . . | 7 . . | . . .",
+            "------+-------+------",
+            ". 2 . | . . . | . 6 .",
+            ". . . | . 8 . | 4 . .",
+            ". . . | . 1 . | . . .",
+            "------+-------+------",
+            ". . . | 6 . 3 | . 7 .",
+            "5 . . | 2 . . | . . .",
+            "1 . 4 | . . . | . . .",
+        ];
+        let sudoku = Sudoku::from_grid_lines(&lines, &board).unwrap();
+        assert_eq!(
+            sudoku.to_line(),
+            Sudoku::from(PUZZLE, &board).unwrap().to_line()
+        );
+        assert_eq!(
+            Sudoku::from_grid_lines(&lines[..10], &board).err(),
+            Some(ParseError::BadLength {
+                expected: 81,
+                got: 72
+            })
+        );
+    }
+
+    #[test]
+    fn validate_finds_duplicate_singles() {
+        let board = SudokuBoard::new();
+        let mut sudoku = Sudoku::from(PUZZLE, &board).unwrap();
+        assert_eq!(sudoku.validate(), Ok(()));
+
+        // A second 4 in the first row, as a contradiction may leave one.
+        let mut four = Candidates::default();
+        four.insert(4);
+        sudoku.cells[2] = four;
+        assert_eq!(
+            sudoku.validate(),
+            Err(Conflict {
+                unit: 0,
+                value: 4,
+                cells: (0, 2)
+            })
+        );
+    }
+
+    #[test]
+    fn try_from_digits() {
+        let board = SudokuBoard::standard();
+        let expected = Sudoku::from(PUZZLE, board).unwrap();
+        let mut digits = [0u8; 81];
+        for (d, c) in digits.iter_mut().zip(PUZZLE.chars()) {
+            *d = c.to_digit(10).unwrap_or(0) as u8;
+        }
+        assert_eq!(
+            Sudoku::try_from(digits).unwrap().to_line(),
+            expected.to_line()
+        );
+        assert_eq!(
+            Sudoku::try_from(&digits[..80]).err(),
+            Some(ParseError::BadLength {
+                expected: 81,
+                got: 80
+            })
+        );
+
+        digits[2] = 12;
+        assert_eq!(
+            Sudoku::try_from(digits).err(),
+            Some(ParseError::BadDigit { cell: 2, value: 12 })
+        );
+        digits[2] = 4;
+        assert_eq!(
+            Sudoku::try_from(digits).err(),
+            Some(ParseError::Contradiction { cell: 2 })
+        );
+    }
+
+    #[test]
+    fn clue_and_filled_counts() {
+        let board = SudokuBoard::new();
+        let sudoku = Sudoku::from(PUZZLE, &board).unwrap();
+        assert_eq!(sudoku.given_count(), 17);
+        // Parsing alone already determines three more cells.
+        assert_eq!(sudoku.clue_count(), 20);
+        assert_eq!(sudoku.filled_count(), 20);
+        let solved = sudoku.solve().unwrap();
+        assert_eq!(solved.given_count(), 17);
+        assert_eq!(solved.clue_count(), 20);
+        assert_eq!(solved.filled_count(), 81);
+
+        // Propagation fills in the last cell of the first row.
+        let almost = format!("12345678.{}", ".".repeat(72));
+        let sudoku = Sudoku::from(&almost, &board).unwrap();
+        assert_eq!(sudoku.given_count(), 8);
+        assert_eq!(sudoku.clue_count(), 9);
+        assert_eq!(sudoku.filled_count(), 9);
+    }
+
+    #[test]
+    fn restrictions_narrow_cells_down() {
+        let board = SudokuBoard::new();
+        let mut sudoku = Sudoku::empty(&board);
+        assert!(sudoku.restrict(0, &[2, 4, 6, 8, 10]));
+        assert_eq!(sudoku.candidates(0), vec![2, 4, 6, 8]);
+        assert!(sudoku.restrict(1, &[2]));
+        assert_eq!(sudoku.value(1), Some(2));
+        assert_eq!(sudoku.candidates(0), vec![4, 6, 8]);
+        assert!(!sudoku.clone().restrict(0, &[1, 3]));
+        assert!(!sudoku.restrict(2, &[]));
+
+        // A solution with an unavoidable set emptied has two, told apart
+        // by ruling out the digit one of them has where they differ.
+        let solution = Sudoku::from(PUZZLE, &board).unwrap().solve().unwrap();
+        let mut line = solution.to_line();
+        for k in &solution.unavoidable_sets(4)[0] {
+            line.replace_range(*k..*k + 1, ".");
+        }
+        let mut sudoku = Sudoku::from(&line, &board).unwrap();
+        let solutions: Vec<Sudoku> = sudoku.solutions().collect();
+        assert_eq!(solutions.len(), 2);
+        let (k, _, theirs) = solutions[0].diff(&solutions[1])[0];
+        let others: Vec<usize> = (1..=9).filter(|v| *v != theirs).collect();
+        assert!(sudoku.restrict(k, &others));
+        assert!(sudoku.solve().unwrap() == solutions[0]);
+        assert!(sudoku.has_unique_solution());
+    }
+
+    #[test]
+    fn eliminations_propagate() {
+        let board = SudokuBoard::new();
+        let mut sudoku = Sudoku::empty(&board);
+        // Down to 9 in cell 0, which clears 9 out of its peers.
+        for v in 1..8 {
+            assert!(sudoku.eliminate(0, v));
+        }
+        assert_eq!(sudoku.filled_count(), 0);
+        assert!(sudoku.eliminate(0, 8));
+        assert_eq!(sudoku.value(0), Some(9));
+        assert!(board
+            .neighbors(0)
+            .iter()
+            .all(|n| !sudoku.candidates(*n).contains(&9)));
+        assert!(sudoku.eliminate(0, 8));
+
+        // The first row now needs its 1 in cell 1 or 2: keep it from both.
+        let mut stuck = sudoku.clone();
+        (3..9).for_each(|k| assert!(stuck.eliminate(k, 1)));
+        assert!(stuck.eliminate(1, 1));
+        assert_eq!(stuck.value(2), Some(1));
+        assert!(!stuck.eliminate(2, 1));
+
+        assert!(sudoku.assign(40, 5));
+        assert_eq!(sudoku.value(40), Some(5));
+        assert!(!sudoku.is_given(40));
+        assert!(!sudoku.clone().assign(41, 5));
+        assert!(!sudoku.clone().assign(41, 0));
+    }
+
+    #[test]
+    fn contradictions_unwind() {
+        let board = SudokuBoard::new();
+        let mut sudoku = Sudoku::empty(&board);
+        (0..7).for_each(|k| assert!(sudoku.assign(k, k + 1)));
+        assert_eq!(sudoku.candidates(7), vec![8, 9]);
+
+        // An 8 at the end of the second row clears it out of both cells
+        // left in the first, leaving each a 9, and the second of them none.
+        let before = sudoku.cells.clone();
+        let mut trail = Trail::new(());
+        let mark = trail.mark();
+        assert!(!sudoku.assign_with(17, 8, &mut trail));
+        assert!(sudoku.cells != before);
+        sudoku.roll_back(&mut trail, mark);
+        assert_eq!(sudoku.cells, before);
+        assert_eq!(
+            sudoku.play(17, 8),
+            Err(MoveError::Contradiction { cell: 17, value: 8 })
+        );
+        assert_eq!(sudoku.cells, before);
+
+        // Every guess the search could make at the root, rolled back
+        // whatever came of it, leaves the grid as it found it.
+        let mut sudoku = Sudoku::from(PUZZLE, &board).unwrap();
+        let before = sudoku.cells.clone();
+        let mut failed = 0;
+        for k in 0..81 {
+            for v in sudoku.candidates(k) {
+                let mark = trail.mark();
+                if !sudoku.assign_with(k, v, &mut trail) {
+                    failed += 1;
+                }
+                sudoku.roll_back(&mut trail, mark);
+                assert_eq!(sudoku.cells, before);
+            }
+        }
+        assert!(failed > 0);
+        assert!(sudoku.solve().unwrap().is_valid_solution());
+    }
+
+    #[test]
+    fn assign_all_names_the_failing_pair() {
+        let board = SudokuBoard::new();
+        let mut sudoku = Sudoku::empty(&board);
+        assert_eq!(sudoku.assign_all(&[(0, 4), (6, 8), (8, 5)]), Ok(()));
+        assert_eq!(sudoku.to_line()[..9], *"4.....8.5");
+        assert!(sudoku.is_given(6) && !sudoku.is_given(1));
+        assert_eq!(sudoku.given_count(), 3);
+        // The 4 in cell 0 rules it out for the rest of the row.
+        assert_eq!(sudoku.assign_all(&[(40, 1), (3, 4), (4, 2)]), Err((3, 4)));
+
+        let mut sudoku = Sudoku::from(PUZZLE, &board).unwrap();
+        assert_eq!(sudoku.assign_all(&[(1, 10)]), Err((1, 10)));
+        let mut sudoku = Sudoku::from(PUZZLE, &board).unwrap();
+        assert_eq!(sudoku.assign_all(&[(1, 0)]), Err((1, 0)));
+    }
+
+    #[test]
+    fn diff_compares_determined_cells() {
+        let board = SudokuBoard::new();
+        let solved = Sudoku::from(PUZZLE, &board).unwrap().solve().unwrap();
+        assert!(solved.diff(&solved).is_empty());
+
+        // The first two solutions the search finds for an empty grid.
+        let empty = Sudoku::empty(&board);
+        let mut solutions = empty.solutions();
+        let (first, second) = (solutions.next().unwrap(), solutions.next().unwrap());
+        // They differ by a rectangle of 6s and 9s.
+        assert_eq!(
+            first.diff(&second),
+            vec![(42, 6, 9), (43, 9, 6), (78, 9, 6), (79, 6, 9)]
+        );
+        assert_eq!(
+            second.diff(&first),
+            vec![(42, 9, 6), (43, 6, 9), (78, 6, 9), (79, 9, 6)]
+        );
+
+        // Of the 81 cells, only the 20 the puzzle has determined count, 3
+        // of them agreeing with the other grid.
+        let puzzle = Sudoku::from(PUZZLE, &board).unwrap();
+        assert_eq!(puzzle.filled_count(), 20);
+        let diff = puzzle.diff(&first);
+        assert_eq!(diff.len(), 17);
+        assert!(diff.iter().all(|(k, v, _)| puzzle.value(*k) == Some(*v)));
+        assert_eq!(diff[0], (0, 4, 1));
+    }
+
+    #[test]
+    fn givens_survive_solving() {
+        let board = SudokuBoard::new();
+        let sudoku = Sudoku::from(PUZZLE, &board).unwrap();
+        let solved = sudoku.solve().unwrap();
+        for (k, c) in PUZZLE.chars().enumerate() {
+            assert_eq!(solved.is_given(k), c != '.');
+            assert_eq!(solved.clone().is_given(k), c != '.');
+        }
+        // Propagation filled in cells that weren't given.
+        assert!((0..81).any(|k| sudoku.value(k).is_some() && !sudoku.is_given(k)));
+
+        // The solution alone gives the puzzle back.
+        let givens: Vec<(usize, usize)> = solved.givens().collect();
+        assert_eq!(givens.len(), 17);
+        assert_eq!(givens[..2], [(0, 4), (6, 8)]);
+        let mut puzzle = Sudoku::empty(&board);
+        assert_eq!(puzzle.assign_all(&givens), Ok(()));
+        assert!(puzzle == sudoku);
+        for solved in [
+            sudoku.try_solve().unwrap(),
+            sudoku.solve_with(ValueOrder::Random(3)).unwrap(),
+            sudoku.solutions().next().unwrap(),
+            sudoku.solve_traced().0.unwrap(),
+        ] {
+            assert!(solved.givens().eq(givens.iter().copied()));
+        }
+
+        let board = SudokuBoard::with_box_size(4);
+        let mut line = ".".repeat(256);
+        line.replace_range(255.., "1");
+        let sudoku = Sudoku::from(&line, &board).unwrap();
+        assert!(sudoku.is_given(255) && !sudoku.is_given(254));
+    }
+
+    #[test]
+    fn candidates_after_propagation() {
+        let board = SudokuBoard::new();
+        let sudoku = Sudoku::from(&format!("123{}", ".".repeat(78)), &board).unwrap();
+        let counts = sudoku.candidate_counts();
+        assert_eq!(counts.len(), 81);
+        assert_eq!(&counts[..4], &[1, 1, 1, 6]);
+        assert_eq!(counts[80], 9);
+        assert_eq!(sudoku.candidates(1), vec![2]);
+        assert_eq!(sudoku.value(1), Some(2));
+        assert_eq!(sudoku.value(9), None);
+        assert_eq!(sudoku.candidates(9), vec![4, 5, 6, 7, 8, 9]);
+        // Below the 1, in its column only.
+        assert_eq!(sudoku.candidates(27), vec![2, 3, 4, 5, 6, 7, 8, 9]);
+        for (k, n) in counts.iter().enumerate() {
+            assert_eq!(*n as usize, sudoku.candidates(k).len());
+        }
+    }
+
+    #[test]
+    fn solutions_are_lazy_and_distinct() {
+        let board = SudokuBoard::new();
+        let sudoku = Sudoku::from(PUZZLE, &board).unwrap();
+        let all: Vec<_> = sudoku.solutions().map(|s| s.to_string()).collect();
+        assert_eq!(all, vec![sudoku.solve().unwrap().to_string()]);
+
+        let empty = Sudoku::empty(&board);
+        let some: Vec<_> = empty.solutions().take(5).map(|s| s.to_string()).collect();
+        assert_eq!(some.len(), 5);
+        assert!((1..5).all(|i| !some[..i].contains(&some[i])));
+
+        let none = Sudoku::from(NO_SOLUTION, &board).unwrap();
+        assert!(none.solutions().next().is_none());
+    }
+
+    #[test]
+    fn grids_compare_by_candidates() {
+        let board = SudokuBoard::new();
+        let sudoku = Sudoku::from(PUZZLE, &board).unwrap();
+        let solved = sudoku.solve().unwrap();
+        assert!(sudoku != solved);
+        assert!(solved == sudoku.solve_with(ValueOrder::Random(3)).unwrap());
+        let owned: Sudoku<'static> = solved.to_line().parse().unwrap();
+        assert!(owned == solved);
+
+        let empty = Sudoku::empty(&board);
+        let mut twice: Vec<Sudoku> = empty.solutions().take(5).collect();
+        twice.extend(empty.solutions().take(5));
+        let distinct: HashSet<Sudoku> = twice.into_iter().collect();
+        assert_eq!(distinct.len(), 5);
+    }
+
+    #[test]
+    fn to_line_round_trips() {
+        let board = SudokuBoard::new();
+        let sudoku = Sudoku::from(PUZZLE, &board).unwrap();
+        let line = sudoku.to_line();
+        let mut givens = PUZZLE.chars().zip(line.chars());
+        assert!(givens.all(|(g, c)| g == '.' || g == c));
+        let reparsed = Sudoku::from(&line, &board).unwrap();
+        assert_eq!(reparsed.to_string(), sudoku.to_string());
+
+        let solved = sudoku.solve().unwrap();
+        let line = solved.to_line();
+        assert!(line.len() == 81 && line.chars().all(|c| ('1'..='9').contains(&c)));
+        let reparsed = Sudoku::from(&line, &board).unwrap();
+        assert_eq!(reparsed.to_string(), solved.to_string());
+    }
+
+    #[test]
+    fn lines_stream_into_one_buffer() {
+        let board = SudokuBoard::new();
+        let solutions: Vec<Sudoku> = Sudoku::empty(&board).solutions().take(3).collect();
+        let mut out = String::new();
+        for solution in &solutions {
+            solution.write_line(&mut out).unwrap();
+            out.push('\n');
+        }
+        let lines: Vec<String> = solutions.iter().map(Sudoku::to_line).collect();
+        assert_eq!(out, lines.join("\n") + "\n");
+
+        let mut line = String::new();
+        Sudoku::from(PUZZLE, &board)
+            .unwrap()
+            .write_line(&mut line)
+            .unwrap();
+        assert_eq!(line.len(), 81);
+        assert!(line.contains('.'));
+    }
+
+    #[test]
+    fn to_bytes_needs_a_solution() {
+        let board = SudokuBoard::new();
+        let sudoku = Sudoku::from(PUZZLE, &board).unwrap();
+        assert_eq!(sudoku.to_bytes(), None);
+        let solved = sudoku.solve().unwrap();
+        let bytes = solved.to_bytes().unwrap();
+        let line: String = bytes.iter().map(|b| char::from(b'0' + b)).collect();
+        assert_eq!(line, solved.to_line());
+
+        let board = SudokuBoard::with_box_size(2);
+        let solved = Sudoku::from(&".".repeat(16), &board).unwrap().solve();
+        assert_eq!(solved.unwrap().to_bytes(), None);
+    }
+
+    #[test]
+    fn from_bytes_round_trips() {
+        let board = SudokuBoard::new();
+        let sudoku = Sudoku::from(PUZZLE, &board).unwrap();
+        let bytes: Vec<u8> = PUZZLE.bytes().map(|b| b.saturating_sub(b'0')).collect();
+        assert!(Sudoku::from_bytes(&bytes, &board).unwrap() == sudoku);
+        let solved = sudoku.solve().unwrap().to_bytes().unwrap();
+        assert_eq!(
+            Sudoku::from_bytes(&solved, &board).unwrap().to_bytes(),
+            Some(solved)
+        );
+
+        assert_eq!(
+            Sudoku::from_bytes(&bytes[..80], &board).err(),
+            Some(ParseError::BadLength {
+                expected: 81,
+                got: 80
+            })
+        );
+        let mut bad = bytes.clone();
+        bad[3] = 10;
+        assert_eq!(
+            Sudoku::from_bytes(&bad, &board).err(),
+            Some(ParseError::BadDigit { cell: 3, value: 10 })
+        );
+        bad[3] = bytes[0];
+        bad[1] = bytes[0];
+        assert!(matches!(
+            Sudoku::from_bytes(&bad, &board).err(),
+            Some(ParseError::Contradiction { .. })
+        ));
+    }
+
+    /// Candidates as flags, to check the search doesn't depend on how
+    /// they're kept.
+    #[derive(Clone, Copy, Default, PartialEq, Eq, Hash, Debug)]
+    struct Flags([bool; 16]);
+
+    impl Cell for Flags {
+        fn all(side: usize) -> Flags {
+            Flags(std::array::from_fn(|i| i < side))
+        }
+
+        fn contains(self, v: usize) -> bool {
+            self.0[v - 1]
+        }
+
+        fn insert(&mut self, v: usize) {
+            self.0[v - 1] = true;
+        }
+
+        fn remove(&mut self, v: usize) {
+            self.0[v - 1] = false;
+        }
+
+        fn len(self) -> usize {
+            self.0.iter().filter(|f| **f).count()
+        }
+
+        fn single(self) -> Option<usize> {
+            (self.len() == 1).then(|| self.iter().next().unwrap())
+        }
+
+        fn iter(self) -> impl Iterator<Item = usize> {
+            (1..=16).filter(move |v| self.0[v - 1])
+        }
+    }
+
+    #[test]
+    fn other_cells_search_alike() {
+        let board = SudokuBoard::new();
+        let top95 = fs::read_to_string("benches/data/top95.txt").unwrap();
+        for line in top95.lines().take(10) {
+            let sudoku = Sudoku::from(line, &board).unwrap();
+            let flags = sudoku.with_cells::<Flags>();
+            assert_eq!(flags.to_line(), sudoku.to_line());
+            let (solved, stats) = flags.solve_stats();
+            assert_eq!(stats, sudoku.solve_stats().1);
+            assert!(solved.unwrap().with_cells::<Candidates>() == sudoku.solve().unwrap());
+        }
+    }
+
+    #[test]
+    fn solves_other_box_sizes() {
+        let board = SudokuBoard::with_box_size(2);
+        let sudoku = Sudoku::from("1.....2..3.....4", &board).unwrap();
+        assert!(sudoku.has_unique_solution());
+        assert_eq!(sudoku.solve().unwrap().to_line(), "1243342143122134");
+        assert_eq!(
+            sudoku.solve().unwrap().to_string(),
+            "1 2 | 4 3 \n3 4 | 2 1 \n----+-----\n4 3 | 1 2 \n2 1 | 3 4 \n\n"
+        );
+
+        let board = SudokuBoard::with_box_size(4);
+        let s = concat!(
+            ".13C..D8.G....5.D.G.6.24....9.3C.F...7CA.3D.EG.2...B....C...AD..",
+            ".7..ED.B..........B..8..9..1..E...69...1.FA...G...4....5...E..D.",
+            "A.8....CB4..D52..C..D.8.15.F6.A...713...E.CDB.8G.G......2.873C91",
+            ".......F...25....9F8C.....53G2B.G....6.2.9.BF3.D.B.EG3...1...67.",
+        );
+        let solved = Sudoku::from(s, &board).unwrap().solve().unwrap();
+        assert!(solved.to_line().starts_with("713C9ED8AG264F5B"));
+        assert_eq!(solved.count_solutions(2), 1);
+    }
+
+    #[test]
+    fn trace_replays_to_the_solution() {
+        let board = SudokuBoard::new();
+        let mut sudoku = Sudoku::from(PUZZLE, &board).unwrap();
+        // Without techniques the search starts guessing right away.
+        sudoku.set_techniques(Techniques::NONE);
+        let (solved, steps) = sudoku.solve_traced();
+        assert_eq!(solved.unwrap().to_line(), sudoku.solve().unwrap().to_line());
+        assert!(matches!(steps[0], Step::Guess { .. }));
+
+        // Replaying the eliminations, undoing each failed branch, must land
+        // on the solution.
+        let mut replay = sudoku.clone();
+        let mut branches: Vec<Vec<(usize, usize)>> = vec![Vec::new()];
+        for step in &steps {
+            match *step {
+                Step::Guess { .. } => branches.push(Vec::new()),
+                Step::Eliminate { cell, value } => {
+                    replay.cells[cell].remove(value);
+                    branches.last_mut().unwrap().push((cell, value));
+                }
+                Step::Backtrack { .. } => {
+                    for (cell, value) in branches.pop().unwrap() {
+                        replay.cells[cell].insert(value);
+                    }
+                }
+                Step::Assign { cell, value } => {
+                    assert_eq!(replay.cells[cell].single(), Some(value))
+                }
+            }
+        }
+        assert_eq!(replay.to_line(), sudoku.solve().unwrap().to_line());
+    }
+
+    #[test]
+    fn parse_matches_from() {
+        let board = SudokuBoard::new();
+        let owned: Sudoku = PUZZLE.parse().unwrap();
+        let borrowed = Sudoku::from(PUZZLE, &board).unwrap();
+        assert_eq!(
+            owned.solve().unwrap().to_string(),
+            borrowed.solve().unwrap().to_string()
+        );
+        assert_eq!(
+            "123".parse::<Sudoku>().err(),
+            Some(ParseError::BadLength {
+                expected: 81,
+                got: 3
+            })
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! Backdoors: cells whose digits, once given, leave nothing to guess.
+
+use super::techniques::{next_choice, LogicalResult};
+use super::Sudoku;
+use crate::candidates::Cell;
+
+impl<'a, C: Cell> Sudoku<'a, C> {
+    /// The smallest backdoors of this grid of at most `max_size` cells,
+    /// each as its cells in increasing order, the sets in increasing order
+    /// too.
+    ///
+    /// A backdoor is a set of cells whose digits in the solution, placed
+    /// on top of the grid, leave the rest to
+    /// [`solve_logical_only`](Sudoku::solve_logical_only), so with the
+    /// grid's own techniques: [`Techniques::NONE`](super::Techniques::NONE)
+    /// makes it propagation alone. The fewer cells it takes, the fewer
+    /// guesses a lucky search needs. Only the cells the techniques leave
+    /// undetermined are tried, every choice of as many of them as it takes,
+    /// so where a single cell does it's quick, but each cell more costs
+    /// about as many times over as there are cells left.
+    ///
+    /// A grid the techniques solve has the empty set for its one backdoor,
+    /// and a grid without a solution has none. With several solutions, the
+    /// digits come from the one [`solve`](Sudoku::solve) finds.
+    pub fn backdoors(&self, max_size: usize) -> Vec<Vec<usize>> {
+        let stuck = match self.solve_logical_only() {
+            LogicalResult::Solved(_) => return vec![Vec::new()],
+            LogicalResult::Contradiction => return Vec::new(),
+            LogicalResult::Stuck(stuck) => stuck,
+        };
+        let Some(solution) = stuck.solve() else {
+            return Vec::new();
+        };
+        let open: Vec<usize> = (0..self.cells.len())
+            .filter(|k| stuck.value(*k).is_none())
+            .collect();
+        let mut found = Vec::new();
+        for size in 1..=max_size.min(open.len()) {
+            let mut chosen: Vec<usize> = (0..size).collect();
+            loop {
+                let mut grid = stuck.clone();
+                let placed = chosen.iter().all(|i| {
+                    let k = open[*i];
+                    grid.assign(k, solution.uniq_val(k))
+                });
+                if placed && matches!(grid.solve_logical_only(), LogicalResult::Solved(_)) {
+                    found.push(chosen.iter().map(|i| open[*i]).collect());
+                }
+                if !next_choice(&mut chosen, open.len()) {
+                    break;
+                }
+            }
+            if !found.is_empty() {
+                break;
+            }
+        }
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::SudokuBoard;
+    use crate::sudoku::{Sudoku, Techniques};
+
+    #[test]
+    fn backdoors_leave_nothing_to_guess() {
+        let board = SudokuBoard::new();
+        let s = "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+        let mut sudoku = Sudoku::from(s, &board).unwrap();
+        sudoku.set_techniques(Techniques::NONE);
+        let solution = sudoku.solve().unwrap();
+        let backdoors = sudoku.backdoors(2);
+        assert!(!backdoors.is_empty());
+        let size = backdoors[0].len();
+        assert!(backdoors.iter().all(|b| b.len() == size));
+        assert!(backdoors.windows(2).all(|w| w[0] < w[1]));
+        for backdoor in &backdoors {
+            let mut grid = sudoku.clone();
+            for k in backdoor {
+                assert!(sudoku.value(*k).is_none());
+                assert!(grid.assign(*k, solution.value(*k).unwrap()));
+            }
+            assert!(grid.is_solved());
+            assert!(grid == solution);
+        }
+        // None smaller.
+        assert!(sudoku.backdoors(size - 1).is_empty());
+
+        // Nothing to guess, nothing to give.
+        assert_eq!(solution.backdoors(2), vec![Vec::<usize>::new()]);
+    }
+}
@@ -0,0 +1,154 @@
+//! Puzzles put together one given at a time, rather than written out as a
+//! string.
+
+use super::{Constraint, Sudoku};
+use crate::board::SudokuBoard;
+use crate::error::ParseError;
+use crate::grid::Grid;
+
+/// Gathers the givens, and any constraints, of a puzzle on a board, then
+/// builds the puzzle in one go.
+///
+/// ```
+/// use sudoku_solver::{SudokuBoard, SudokuBuilder};
+///
+/// let board = SudokuBoard::new();
+/// let sudoku = SudokuBuilder::new(&board)
+///     .set(0, 4)
+///     .set_at(0, 6, 8)
+///     .build()
+///     .unwrap();
+/// assert_eq!(sudoku.value(6), Some(8));
+/// ```
+#[derive(Clone)]
+pub struct SudokuBuilder<'a> {
+    board: &'a SudokuBoard,
+    digits: Vec<usize>,
+    constraints: Vec<Constraint>,
+}
+
+impl<'a> SudokuBuilder<'a> {
+    /// A builder for a puzzle on `board` with no givens yet.
+    pub fn new(board: &'a SudokuBoard) -> SudokuBuilder<'a> {
+        SudokuBuilder {
+            board,
+            digits: vec![0; board.cell_count()],
+            constraints: Vec::new(),
+        }
+    }
+
+    /// Gives `cell` the digit `value`, replacing any digit set before. A
+    /// `value` of `0` empties the cell again.
+    ///
+    /// # Panics
+    ///
+    /// If `cell` isn't on the board.
+    pub fn set(&mut self, cell: usize, value: usize) -> &mut Self {
+        self.digits[cell] = value;
+        self
+    }
+
+    /// Like [`set`](SudokuBuilder::set), for the cell at `row` and `col`.
+    ///
+    /// # Panics
+    ///
+    /// If the cell isn't on the board.
+    pub fn set_at(&mut self, row: usize, col: usize, value: usize) -> &mut Self {
+        let side = self.board.side();
+        assert!(row < side && col < side, "cell out of the board");
+        self.set(self.board.cell_index(row, col), value)
+    }
+
+    /// Adds `constraint` for the puzzle to satisfy, as
+    /// [`Sudoku::with_constraints`] does.
+    ///
+    /// # Panics
+    ///
+    /// If the constraint is about a cell that isn't on the board.
+    pub fn constraint(&mut self, constraint: Constraint) -> &mut Self {
+        assert!(
+            constraint
+                .cells()
+                .iter()
+                .all(|k| *k < self.board.cell_count()),
+            "constraint cell out of the board"
+        );
+        self.constraints.push(constraint);
+        self
+    }
+
+    /// The puzzle with every given set so far, placed in cell order the way
+    /// [`Sudoku::from_grid`] places them.
+    ///
+    /// Fails if some digit is too large for the board, or contradicts the
+    /// givens in the cells before it.
+    pub fn build(&self) -> Result<Sudoku<'a>, ParseError> {
+        let side = self.board.side();
+        if let Some((cell, &value)) = self.digits.iter().enumerate().find(|(_, v)| **v > side) {
+            return Err(ParseError::BadDigit { cell, value });
+        }
+        let digits: Vec<u8> = self.digits.iter().map(|v| *v as u8).collect();
+        let mut sudoku = Sudoku::from_grid(&Grid::from(&digits[..]), self.board)?;
+        sudoku.constraints.clone_from(&self.constraints);
+        Ok(sudoku)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SudokuBuilder;
+    use crate::board::SudokuBoard;
+    use crate::error::ParseError;
+    use crate::sudoku::{Constraint, Sudoku};
+
+    const PUZZLE: &str =
+        "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+
+    #[test]
+    fn builds_what_parsing_does() {
+        let board = SudokuBoard::new();
+        let mut builder = SudokuBuilder::new(&board);
+        for (k, c) in PUZZLE.chars().enumerate() {
+            if let Some(v) = c.to_digit(10) {
+                builder.set(k, v as usize);
+            }
+        }
+        let built = builder.build().unwrap();
+        assert_eq!(
+            built.to_line(),
+            Sudoku::from(PUZZLE, &board).unwrap().to_line()
+        );
+        assert_eq!(built.given_count(), 17);
+
+        // Setting a cell twice keeps the last digit, and 0 clears it.
+        builder.set_at(0, 0, 0).set(1, 4).set(1, 1);
+        let built = builder.build().unwrap();
+        assert_eq!((built.value(0), built.value(1)), (None, Some(1)));
+    }
+
+    #[test]
+    fn conflicts_fail_the_build() {
+        let board = SudokuBoard::new();
+        let conflict = SudokuBuilder::new(&board).set(3, 7).set_at(4, 3, 7).build();
+        assert_eq!(conflict.err(), Some(ParseError::Contradiction { cell: 39 }));
+        let too_large = SudokuBuilder::new(&board).set(5, 300).build();
+        assert_eq!(
+            too_large.err(),
+            Some(ParseError::BadDigit {
+                cell: 5,
+                value: 300
+            })
+        );
+
+        let ordered = SudokuBuilder::new(&board)
+            .constraint(Constraint::GreaterThan {
+                higher: 1,
+                lower: 0,
+            })
+            .set(0, 9)
+            .build()
+            .unwrap();
+        assert_eq!(ordered.constraints().len(), 1);
+        assert!(ordered.solve().is_none());
+    }
+}
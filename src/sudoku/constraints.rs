@@ -0,0 +1,218 @@
+//! Constraints on top of the board's units, for sudoku variants: each one
+//! rules out candidates before every guess of the search.
+
+use super::{Cage, Mark, Sudoku, Thermo};
+use crate::board::SudokuBoard;
+use crate::candidates::Cell;
+use crate::error::{Contradiction, ParseError};
+use crate::journal::Journal;
+
+/// A rule some cells must follow besides holding distinct digits in every
+/// unit.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Constraint {
+    /// The killer sudoku cage: see [`Cage`].
+    Cage(Cage),
+    /// The digit of `higher` is greater than the digit of `lower`, as
+    /// marked by a `>` between them in comparison sudoku.
+    GreaterThan { higher: usize, lower: usize },
+    /// The digits of the two cells relate as `mark` says, as in XV and
+    /// Kropki sudoku.
+    Marked { cells: [usize; 2], mark: Mark },
+    /// The digits of the two cells don't relate as `mark` says: the two
+    /// cells have no such mark between them where every mark is shown.
+    Unmarked { cells: [usize; 2], mark: Mark },
+    /// The thermometer of thermo sudoku: see [`Thermo`].
+    Thermo(Thermo),
+}
+
+impl Constraint {
+    /// The cells the constraint is about.
+    pub(super) fn cells(&self) -> Vec<usize> {
+        match self {
+            Constraint::Cage(cage) => cage.cells.clone(),
+            Constraint::Thermo(thermo) => thermo.cells.clone(),
+            Constraint::GreaterThan { higher, lower } => vec![*higher, *lower],
+            Constraint::Marked { cells, .. } | Constraint::Unmarked { cells, .. } => cells.to_vec(),
+        }
+    }
+
+    /// Whether the constraint holds once every cell has a digit, `digit`
+    /// giving the digit of each.
+    pub(super) fn holds(&self, digit: impl Fn(usize) -> usize) -> bool {
+        match self {
+            Constraint::Cage(cage) => {
+                let mut digits: Vec<usize> = cage.cells.iter().map(|k| digit(*k)).collect();
+                digits.sort_unstable();
+                digits.dedup();
+                digits.len() == cage.cells.len() && digits.iter().sum::<usize>() == cage.sum
+            }
+            Constraint::GreaterThan { higher, lower } => digit(*higher) > digit(*lower),
+            Constraint::Marked {
+                cells: [a, b],
+                mark,
+            } => mark.relates(digit(*a), digit(*b)),
+            Constraint::Unmarked {
+                cells: [a, b],
+                mark,
+            } => !mark.relates(digit(*a), digit(*b)),
+            Constraint::Thermo(thermo) => {
+                thermo.cells.windows(2).all(|w| digit(w[0]) < digit(w[1]))
+            }
+        }
+    }
+}
+
+impl<'a> Sudoku<'a> {
+    /// Parses a puzzle like [`Sudoku::from`], with `constraints` to satisfy
+    /// on top of the board's units. The search enforces them, together with
+    /// the board's units, before each guess.
+    ///
+    /// # Panics
+    ///
+    /// If some constraint is about a cell that isn't on the board.
+    pub fn with_constraints(
+        s: &str,
+        board: &'a SudokuBoard,
+        constraints: Vec<Constraint>,
+    ) -> Result<Sudoku<'a>, ParseError> {
+        assert!(
+            constraints
+                .iter()
+                .flat_map(Constraint::cells)
+                .all(|k| k < board.cell_count()),
+            "constraint cell out of the board"
+        );
+        let mut sudoku = Sudoku::from(s, board)?;
+        sudoku.constraints = constraints;
+        Ok(sudoku)
+    }
+
+    /// Parses a comparison puzzle: like [`Sudoku::from`], with the digit of
+    /// the first cell of each pair of `greater` above that of the second.
+    ///
+    /// # Panics
+    ///
+    /// If some pair has a cell that isn't on the board.
+    pub fn with_inequalities(
+        s: &str,
+        board: &'a SudokuBoard,
+        greater: &[(usize, usize)],
+    ) -> Result<Sudoku<'a>, ParseError> {
+        let constraints = greater
+            .iter()
+            .map(|&(higher, lower)| Constraint::GreaterThan { higher, lower })
+            .collect();
+        Sudoku::with_constraints(s, board, constraints)
+    }
+}
+
+impl<'a, C: Cell> Sudoku<'a, C> {
+    /// The constraints this grid must satisfy, none for a plain sudoku.
+    #[inline]
+    pub fn constraints(&self) -> &[Constraint] {
+        &self.constraints
+    }
+
+    pub(super) fn constraints_pass<J: Journal>(
+        &mut self,
+        j: &mut J,
+    ) -> Result<bool, Contradiction> {
+        let mut progress = false;
+        for c in 0..self.constraints.len() {
+            progress |= match &self.constraints[c] {
+                Constraint::Cage(cage) => {
+                    let (cells, sum) = (cage.cells.clone(), cage.sum);
+                    self.cage_pass(&cells, sum, j)?
+                }
+                &Constraint::GreaterThan { higher, lower } => {
+                    self.greater_than_pass(higher, lower, j)?
+                }
+                &Constraint::Marked { cells, mark } => {
+                    self.pair_pass(cells, |v, w| mark.relates(v, w), j)?
+                }
+                &Constraint::Unmarked { cells, mark } => {
+                    self.pair_pass(cells, |v, w| !mark.relates(v, w), j)?
+                }
+                Constraint::Thermo(thermo) => {
+                    let cells = thermo.cells.clone();
+                    self.thermo_pass(&cells, j)?
+                }
+            };
+        }
+        Ok(progress)
+    }
+
+    /// Rules out the digits of `higher` no greater than every digit left for
+    /// `lower`, and those of `lower` no smaller than every digit left for
+    /// `higher`.
+    fn greater_than_pass<J: Journal>(
+        &mut self,
+        higher: usize,
+        lower: usize,
+        j: &mut J,
+    ) -> Result<bool, Contradiction> {
+        let mut progress = false;
+        let floor = self.cells[lower].iter().next().ok_or(Contradiction)?;
+        for v in self.cells[higher].iter().filter(|v| *v <= floor) {
+            progress = true;
+            if !self.eliminate_with(higher, v, j) {
+                return Err(Contradiction);
+            }
+        }
+        let ceiling = self.cells[higher].iter().last().ok_or(Contradiction)?;
+        for v in self.cells[lower].iter().filter(|v| *v >= ceiling) {
+            progress = true;
+            if !self.eliminate_with(lower, v, j) {
+                return Err(Contradiction);
+            }
+        }
+        Ok(progress)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::SudokuBoard;
+    use crate::error::Contradiction;
+    use crate::sudoku::{Constraint, Sudoku};
+
+    #[test]
+    fn inequalities_prune_candidates() {
+        let board = SudokuBoard::new();
+        let puzzle = format!(".8{}", ".".repeat(79));
+        let mut sudoku = Sudoku::with_inequalities(&puzzle, &board, &[(0, 1), (9, 10)]).unwrap();
+        assert_eq!(sudoku.reduce(&mut ()), Ok(()));
+        assert_eq!(sudoku.value(0), Some(9));
+        // The box has no 8 or 9 left for cells 9 and 10, one above the other.
+        assert_eq!(sudoku.candidates(9), vec![2, 3, 4, 5, 6, 7]);
+        assert_eq!(sudoku.candidates(10), vec![1, 2, 3, 4, 5, 6]);
+
+        let puzzle = format!("1{}", ".".repeat(80));
+        let mut sudoku = Sudoku::with_inequalities(&puzzle, &board, &[(0, 1)]).unwrap();
+        assert_eq!(sudoku.reduce(&mut ()), Err(Contradiction));
+    }
+
+    #[test]
+    fn inequalities_compose_with_units() {
+        let board = SudokuBoard::new();
+        // A descending first row, and a first column going up.
+        let mut greater: Vec<(usize, usize)> = (0..8).map(|c| (c, c + 1)).collect();
+        greater.extend((1..8).map(|r| ((r + 1) * 9, r * 9)));
+        let sudoku = Sudoku::with_inequalities(&".".repeat(81), &board, &greater).unwrap();
+        let solved = sudoku.solve().unwrap();
+        assert_eq!(&solved.to_line()[..9], "987654321");
+        let column: Vec<usize> = (1..9).map(|r| solved.value(r * 9).unwrap()).collect();
+        assert!(column.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(solved.constraints().len(), 15);
+        assert!(solved.is_valid_solution());
+
+        // The same digits, checked against the opposite order.
+        let mut reversed = solved.clone();
+        reversed.constraints = vec![Constraint::GreaterThan {
+            higher: 1,
+            lower: 0,
+        }];
+        assert!(!reversed.is_valid_solution());
+    }
+}
@@ -0,0 +1,238 @@
+use super::Sudoku;
+use crate::board::SudokuBoard;
+use crate::candidates::Cell;
+use crate::stats::Difficulty;
+use crate::symmetry::SymmetryKind;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// Generates a random puzzle with exactly one solution.
+///
+/// Starts from a random solved grid and takes its digits away one at a
+/// time, in random order, putting back any whose removal would make the
+/// solution ambiguous. Stops once only `clues` givens are left, or when no
+/// more can go, so the result may have more than `clues` givens.
+pub fn generate<'a, R: Rng>(board: &'a SudokuBoard, clues: usize, rng: &mut R) -> Sudoku<'a> {
+    generate_symmetric(board, clues, SymmetryKind::None, rng)
+}
+
+/// Like [`generate`], with the givens laid out with the given `symmetry`:
+/// each digit is taken away together with the one in the cell it mirrors
+/// to, or put back together with it. Both count towards `clues`, and a
+/// pair is left alone if taking it away would go below them, so a result
+/// may have one given more than it asks for, but never fewer.
+pub fn generate_symmetric<'a, R: Rng>(
+    board: &'a SudokuBoard,
+    clues: usize,
+    symmetry: SymmetryKind,
+    rng: &mut R,
+) -> Sudoku<'a> {
+    let mut puzzle = Sudoku::empty(board);
+    let givens = dig(&mut puzzle, clues, symmetry, rng);
+    place_givens(&mut puzzle, &givens);
+    puzzle
+}
+
+/// The digits [`generate_symmetric`] keeps of a random solved grid, in
+/// cell order, `0` for each cell it empties. Each choice is tried out in
+/// `puzzle`, which is left holding the last one.
+fn dig<R: Rng>(
+    puzzle: &mut Sudoku,
+    clues: usize,
+    symmetry: SymmetryKind,
+    rng: &mut R,
+) -> Vec<usize> {
+    let board = puzzle.board;
+    let solution = random_grid(&Sudoku::empty(board), rng).expect("the empty grid is solvable");
+    let size = board.cell_count();
+    let mut givens: Vec<usize> = (0..size).map(|k| solution.uniq_val(k)).collect();
+
+    let mut order: Vec<usize> = (0..size).collect();
+    order.shuffle(rng);
+    let mut left = size;
+    for k in order {
+        if left <= clues {
+            break;
+        }
+        // Gone already, along with the cell it mirrors.
+        if givens[k] == 0 {
+            continue;
+        }
+        let m = symmetry.mirror(board.side(), k);
+        let taken = if m == k { 1 } else { 2 };
+        if left - taken < clues {
+            continue;
+        }
+        let (v, w) = (givens[k], givens[m]);
+        givens[k] = 0;
+        givens[m] = 0;
+        place_givens(puzzle, &givens);
+        if puzzle.has_unique_solution() {
+            left -= taken;
+        } else {
+            givens[k] = v;
+            givens[m] = w;
+        }
+    }
+    givens
+}
+
+/// How many puzzles [`generate_with_difficulty`] tries before settling.
+const DIFFICULTY_ATTEMPTS: usize = 50;
+
+/// Generates a random puzzle with exactly one solution whose
+/// [`Difficulty`] is `target`, if one turns up, along with the difficulty
+/// it has.
+///
+/// Puzzles come from [`generate`], with as few givens as it can leave,
+/// and are rated until one falls in the `target` band. After a bounded
+/// number of tries the puzzle of the closest band seen is taken instead:
+/// with every technique on, most puzzles rate Trivial or Easy, and the
+/// bands past Medium are rarely hit.
+pub fn generate_with_difficulty<'a, R: Rng>(
+    board: &'a SudokuBoard,
+    target: Difficulty,
+    rng: &mut R,
+) -> (Sudoku<'a>, Difficulty) {
+    generate_symmetric_with_difficulty(board, target, SymmetryKind::None, rng)
+}
+
+/// Like [`generate_with_difficulty`], the puzzles coming from
+/// [`generate_symmetric`] with the given `symmetry`. Symmetric puzzles
+/// keep more givens, so tend to rate easier still.
+pub fn generate_symmetric_with_difficulty<'a, R: Rng>(
+    board: &'a SudokuBoard,
+    target: Difficulty,
+    symmetry: SymmetryKind,
+    rng: &mut R,
+) -> (Sudoku<'a>, Difficulty) {
+    let distance = |d: Difficulty| (d as usize).abs_diff(target as usize);
+    let mut best: Option<(Sudoku<'a>, Difficulty)> = None;
+    for _ in 0..DIFFICULTY_ATTEMPTS {
+        let puzzle = generate_symmetric(board, 0, symmetry, rng);
+        let difficulty = puzzle.difficulty();
+        if difficulty == target {
+            return (puzzle, difficulty);
+        }
+        if best
+            .as_ref()
+            .is_none_or(|(_, d)| distance(difficulty) < distance(*d))
+        {
+            best = Some((puzzle, difficulty));
+        }
+    }
+    best.expect("at least one attempt")
+}
+
+/// A random completion of `s`: the same search as `solve`, trying each
+/// cell's candidates in random order.
+fn random_grid<'a, R: Rng>(s: &Sudoku<'a>, rng: &mut R) -> Option<Sudoku<'a>> {
+    if s.is_solved() {
+        return Some(s.clone());
+    }
+    let k = s.select_cell();
+    let mut candidates: Vec<usize> = s.cells[k].iter().collect();
+    candidates.shuffle(rng);
+    candidates.into_iter().find_map(|v| {
+        let mut t = s.clone();
+        t.assign(k, v).then(|| random_grid(&t, rng)).flatten()
+    })
+}
+
+/// Resets `s` to hold just the digits in `givens`, `0` meaning empty. The
+/// digits must come from a valid solution, so they can't contradict.
+fn place_givens(s: &mut Sudoku, givens: &[usize]) {
+    s.reset();
+    let givens = givens.iter().copied().enumerate().filter(|(_, v)| *v != 0);
+    s.assign_givens(givens).expect("givens from a solution");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        dig, generate, generate_symmetric, generate_symmetric_with_difficulty,
+        generate_with_difficulty,
+    };
+    use crate::board::SudokuBoard;
+    use crate::grid::Grid;
+    use crate::stats::Difficulty;
+    use crate::sudoku::Sudoku;
+    use crate::symmetry::SymmetryKind;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn generates_unique_puzzles() {
+        let board = SudokuBoard::new();
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..3 {
+            let puzzle = generate(&board, 24, &mut rng);
+            assert!(puzzle.given_count() < board.cell_count());
+            assert!(puzzle.has_unique_solution());
+            assert!(puzzle.given_count() >= 24);
+            assert!(puzzle.solve().unwrap().is_solved());
+        }
+        let givens = dig(&mut Sudoku::empty(&board), 24, SymmetryKind::None, &mut rng);
+        assert!(givens.iter().filter(|v| **v != 0).count() >= 24);
+    }
+
+    #[test]
+    fn generates_symmetric_puzzles() {
+        let board = SudokuBoard::new();
+        let mut rng = StdRng::seed_from_u64(1);
+        for symmetry in [
+            SymmetryKind::Rotational180,
+            SymmetryKind::Diagonal,
+            SymmetryKind::Horizontal,
+            SymmetryKind::Vertical,
+        ] {
+            let puzzle = generate_symmetric(&board, 0, symmetry, &mut rng);
+            assert!(puzzle.has_unique_solution());
+            assert!((0..81).all(|k| puzzle.is_given(k) == puzzle.is_given(symmetry.mirror(9, k))));
+        }
+        let puzzle = generate_symmetric(&board, 0, SymmetryKind::Rotational180, &mut rng);
+        let mut line = ".".repeat(81);
+        for (k, v) in puzzle.givens() {
+            line.replace_range(k..k + 1, &v.to_string());
+        }
+        let grid = Grid::parse(&line, &board).unwrap();
+        assert_eq!(grid.clue_symmetry(), SymmetryKind::Rotational180);
+
+        // Pairs come off two at a time, but never past the target, whatever
+        // its parity and however many cells mirror to themselves.
+        for symmetry in [SymmetryKind::Rotational180, SymmetryKind::Diagonal] {
+            for clues in [40, 41, 40, 41] {
+                let puzzle = generate_symmetric(&board, clues, symmetry, &mut rng);
+                assert!((clues..=clues + 1).contains(&puzzle.given_count()));
+            }
+        }
+
+        let (puzzle, difficulty) = generate_symmetric_with_difficulty(
+            &board,
+            Difficulty::Easy,
+            SymmetryKind::Vertical,
+            &mut rng,
+        );
+        assert_eq!(puzzle.difficulty(), difficulty);
+        assert!((0..81).all(|k| puzzle.is_given(k) == puzzle.is_given(k / 9 * 9 + 8 - k % 9)));
+    }
+
+    #[test]
+    fn generates_the_difficulty_asked_for() {
+        let board = SudokuBoard::new();
+        let mut rng = StdRng::seed_from_u64(1);
+        for target in [Difficulty::Trivial, Difficulty::Easy] {
+            let (puzzle, difficulty) = generate_with_difficulty(&board, target, &mut rng);
+            assert_eq!(difficulty, target);
+            assert_eq!(puzzle.difficulty(), target);
+            assert!(puzzle.has_unique_solution());
+        }
+
+        // Every 4x4 puzzle falls to propagation: the closest band is all
+        // there is.
+        let board = SudokuBoard::with_box_size(2);
+        let (puzzle, difficulty) = generate_with_difficulty(&board, Difficulty::Hard, &mut rng);
+        assert_eq!(difficulty, Difficulty::Trivial);
+        assert!(puzzle.has_unique_solution());
+    }
+}
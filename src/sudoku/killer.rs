@@ -0,0 +1,207 @@
+//! Killer sudoku: cages of cells whose digits must add up to a given sum,
+//! with no digit repeated within a cage.
+
+use super::{Constraint, Sudoku};
+use crate::board::SudokuBoard;
+use crate::candidates::Cell;
+use crate::error::{Contradiction, ParseError};
+use crate::journal::Journal;
+
+/// Cells whose digits must add up to `sum`, each digit at most once.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Cage {
+    pub cells: Vec<usize>,
+    pub sum: usize,
+}
+
+impl<'a> Sudoku<'a> {
+    /// Parses a puzzle like [`Sudoku::from`], with `cages` to satisfy on top
+    /// of the board's units. Killer puzzles usually have no givens at all,
+    /// in which case `s` is all dots.
+    ///
+    /// The cages are enforced by the search: before each guess every cage
+    /// rules out the digits that would push its sum out of reach, using the
+    /// smallest and largest candidates left in its other cells.
+    ///
+    /// # Panics
+    ///
+    /// If some cage has a cell that isn't on the board.
+    pub fn with_cages(
+        s: &str,
+        board: &'a SudokuBoard,
+        cages: Vec<Cage>,
+    ) -> Result<Sudoku<'a>, ParseError> {
+        Sudoku::with_constraints(s, board, cages.into_iter().map(Constraint::Cage).collect())
+    }
+}
+
+impl<'a, C: Cell> Sudoku<'a, C> {
+    /// The cages this grid must satisfy, none for a plain sudoku.
+    pub fn cages(&self) -> impl Iterator<Item = &Cage> {
+        self.constraints().iter().filter_map(|c| match c {
+            Constraint::Cage(cage) => Some(cage),
+            _ => None,
+        })
+    }
+
+    pub(super) fn cage_pass<J: Journal>(
+        &mut self,
+        cells: &[usize],
+        sum: usize,
+        j: &mut J,
+    ) -> Result<bool, Contradiction> {
+        let mut progress = false;
+
+        // No repeats: a determined digit is out for the rest of the cage.
+        for &a in cells {
+            let Some(v) = self.cells[a].single() else {
+                continue;
+            };
+            for &b in cells.iter().filter(|b| **b != a) {
+                if self.cells[b].contains(v) {
+                    progress = true;
+                    if !self.eliminate_with(b, v, j) {
+                        return Err(Contradiction);
+                    }
+                }
+            }
+        }
+
+        // Bounds: each digit must leave the rest of the cage able to make up
+        // the difference.
+        let bounds = |s: &Sudoku<'a, C>, k: usize| {
+            let mut vs = s.cells[k].iter();
+            let min = vs.next().ok_or(Contradiction)?;
+            Ok((min, vs.last().unwrap_or(min)))
+        };
+        let (mut lo, mut hi) = (0, 0);
+        for &k in cells {
+            let (min, max) = bounds(self, k)?;
+            lo += min;
+            hi += max;
+        }
+        if sum < lo || sum > hi {
+            return Err(Contradiction);
+        }
+        for &k in cells {
+            let (min, max) = bounds(self, k)?;
+            let (rest_lo, rest_hi) = (lo - min, hi - max);
+            for v in self.cells[k].iter() {
+                if rest_lo + v > sum || rest_hi + v < sum {
+                    progress = true;
+                    if !self.eliminate_with(k, v, j) {
+                        return Err(Contradiction);
+                    }
+                }
+            }
+            // Eliminating may have narrowed any cell, so start over on the
+            // next pass rather than work from stale bounds.
+            if progress {
+                break;
+            }
+        }
+        Ok(progress)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cage;
+    use crate::board::SudokuBoard;
+    use crate::error::Contradiction;
+    use crate::sudoku::Sudoku;
+
+    /// A killer puzzle with no givens and a unique solution.
+    const KILLER: [(&[usize], usize); 33] = [
+        (&[0, 9], 10),
+        (&[1, 10], 4),
+        (&[2, 3, 4], 16),
+        (&[5, 14, 23], 21),
+        (&[6, 7, 8], 15),
+        (&[11, 12, 21], 10),
+        (&[13, 22], 7),
+        (&[15, 24, 33], 13),
+        (&[16, 25], 5),
+        (&[17, 26, 35], 22),
+        (&[18, 19, 20], 22),
+        (&[27, 28], 10),
+        (&[29, 30], 9),
+        (&[31, 32], 10),
+        (&[34, 43, 52], 14),
+        (&[36, 37, 38], 17),
+        (&[39, 48, 49], 15),
+        (&[40, 41], 14),
+        (&[42, 51, 60], 16),
+        (&[44, 53, 62], 11),
+        (&[45, 46, 55], 15),
+        (&[47, 56, 65], 18),
+        (&[50, 59], 5),
+        (&[54, 63], 7),
+        (&[57, 66], 8),
+        (&[58, 67, 68], 14),
+        (&[61, 70, 79], 24),
+        (&[64, 73], 13),
+        (&[69, 78], 8),
+        (&[71, 80], 7),
+        (&[72], 1),
+        (&[74, 75, 76], 19),
+        (&[77], 5),
+    ];
+
+    fn cages(cages: &[(&[usize], usize)]) -> Vec<Cage> {
+        cages
+            .iter()
+            .map(|(cells, sum)| Cage {
+                cells: cells.to_vec(),
+                sum: *sum,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn cages_bound_candidates() {
+        let board = SudokuBoard::new();
+        let mut sudoku =
+            Sudoku::with_cages(&".".repeat(81), &board, cages(&[(&[0, 1], 3), (&[2], 9)])).unwrap();
+        assert_eq!(sudoku.reduce(&mut ()), Ok(()));
+        assert_eq!(sudoku.candidates(0), vec![1, 2]);
+        assert_eq!(sudoku.candidates(1), vec![1, 2]);
+        assert_eq!(sudoku.candidates(2), vec![9]);
+
+        // Two distinct digits can't add up to 2.
+        let mut sudoku =
+            Sudoku::with_cages(&".".repeat(81), &board, cages(&[(&[0, 40], 2)])).unwrap();
+        assert_eq!(sudoku.reduce(&mut ()), Err(Contradiction));
+    }
+
+    #[test]
+    fn solves_killer_puzzle() {
+        let board = SudokuBoard::new();
+        let sudoku = Sudoku::with_cages(&".".repeat(81), &board, cages(&KILLER)).unwrap();
+        assert_eq!(sudoku.cages().count(), 33);
+        assert_eq!(sudoku.count_solutions(2), 1);
+        let solved = sudoku.solve().unwrap();
+        assert_eq!(
+            solved.to_line(),
+            "417369825632158947958724316825437169791586432346912758289643571573291684164875293"
+        );
+        for cage in solved.cages() {
+            let sum: usize = cage.cells.iter().map(|k| solved.value(*k).unwrap()).sum();
+            assert_eq!(sum, cage.sum);
+        }
+
+        let small = SudokuBoard::with_box_size(2);
+        let small_cages = [
+            (&[0, 1, 5][..], 7),
+            (&[2, 3, 7], 8),
+            (&[4, 8], 7),
+            (&[6, 10], 3),
+            (&[9, 13], 4),
+            (&[11, 15], 6),
+            (&[12], 2),
+            (&[14], 3),
+        ];
+        let sudoku = Sudoku::with_cages(&".".repeat(16), &small, cages(&small_cages)).unwrap();
+        assert_eq!(sudoku.solve().unwrap().to_line(), "1243342143122134");
+    }
+}
@@ -0,0 +1,230 @@
+//! Marks between orthogonally adjacent cells: the X and V of XV sudoku and
+//! the white and black dots of Kropki sudoku, each relating the digits of
+//! the two cells it sits between.
+
+use super::{Constraint, Sudoku};
+use crate::board::SudokuBoard;
+use crate::candidates::Cell;
+use crate::error::{Contradiction, ParseError};
+use crate::journal::Journal;
+
+/// A mark between two adjacent cells, constraining their digits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mark {
+    /// The digits add up to 10.
+    X,
+    /// The digits add up to 5.
+    V,
+    /// A white dot: the digits are consecutive.
+    White,
+    /// A black dot: one digit is twice the other.
+    Black,
+}
+
+impl Mark {
+    /// Whether digits `v` and `w` on either side of the mark go with it.
+    pub fn relates(self, v: usize, w: usize) -> bool {
+        match self {
+            Mark::X => v + w == 10,
+            Mark::V => v + w == 5,
+            Mark::White => v.abs_diff(w) == 1,
+            Mark::Black => v == 2 * w || w == 2 * v,
+        }
+    }
+}
+
+impl<'a> Sudoku<'a> {
+    /// Parses a Kropki puzzle: like [`Sudoku::from`], with a white or black
+    /// dot between the two cells of each of `dots`.
+    ///
+    /// As the rules go, every dot is shown: two adjacent cells without a dot
+    /// hold digits that are neither consecutive nor one twice the other.
+    ///
+    /// # Panics
+    ///
+    /// If some dot is an X or a V, or is on a cell that isn't on the board.
+    pub fn with_dots(
+        s: &str,
+        board: &'a SudokuBoard,
+        dots: &[(usize, usize, Mark)],
+    ) -> Result<Sudoku<'a>, ParseError> {
+        Sudoku::with_marks(s, board, dots, [Mark::White, Mark::Black])
+    }
+
+    /// Parses an XV puzzle: like [`Sudoku::from`], with an X or a V between
+    /// the two cells of each of `marks`.
+    ///
+    /// As the rules go, every mark is shown: two adjacent cells without one
+    /// hold digits adding up to neither 5 nor 10.
+    ///
+    /// # Panics
+    ///
+    /// If some mark is a dot, or is on a cell that isn't on the board.
+    pub fn with_xv(
+        s: &str,
+        board: &'a SudokuBoard,
+        marks: &[(usize, usize, Mark)],
+    ) -> Result<Sudoku<'a>, ParseError> {
+        Sudoku::with_marks(s, board, marks, [Mark::X, Mark::V])
+    }
+
+    /// `marks` as constraints, all of them of one of the two `kinds`, plus
+    /// the constraint that the adjacent cells left unmarked don't relate
+    /// by either kind.
+    fn with_marks(
+        s: &str,
+        board: &'a SudokuBoard,
+        marks: &[(usize, usize, Mark)],
+        kinds: [Mark; 2],
+    ) -> Result<Sudoku<'a>, ParseError> {
+        assert!(
+            marks.iter().all(|(_, _, m)| kinds.contains(m)),
+            "marks of the wrong kind for the variant"
+        );
+        let mut constraints: Vec<Constraint> = marks
+            .iter()
+            .map(|&(a, b, mark)| Constraint::Marked {
+                cells: [a, b],
+                mark,
+            })
+            .collect();
+        let side = board.side();
+        let marked = |a: usize, b: usize| {
+            marks
+                .iter()
+                .any(|&(p, q, _)| (p, q) == (a, b) || (p, q) == (b, a))
+        };
+        for a in 0..board.cell_count() {
+            let right = (board.col_of(a) + 1 < side).then_some(a + 1);
+            let below = (board.row_of(a) + 1 < side).then_some(a + side);
+            for b in right.into_iter().chain(below) {
+                if !marked(a, b) {
+                    constraints.extend(kinds.map(|mark| Constraint::Unmarked {
+                        cells: [a, b],
+                        mark,
+                    }));
+                }
+            }
+        }
+        Sudoku::with_constraints(s, board, constraints)
+    }
+}
+
+impl<'a, C: Cell> Sudoku<'a, C> {
+    /// The marks and dots this grid must satisfy, as `(a, b, mark)`, none
+    /// for a plain sudoku.
+    pub fn marks(&self) -> impl Iterator<Item = (usize, usize, Mark)> + '_ {
+        self.constraints().iter().filter_map(|c| match c {
+            &Constraint::Marked {
+                cells: [a, b],
+                mark,
+            } => Some((a, b, mark)),
+            _ => None,
+        })
+    }
+
+    /// Rules out the digits of either cell of `cells` that no digit left
+    /// in the other goes with, `allowed` telling which pairs go together.
+    /// Cells sharing a unit can't hold the same digit, whatever `allowed`
+    /// says.
+    pub(super) fn pair_pass<J: Journal>(
+        &mut self,
+        cells: [usize; 2],
+        allowed: impl Fn(usize, usize) -> bool,
+        j: &mut J,
+    ) -> Result<bool, Contradiction> {
+        let peers = self.board.neighbors(cells[0]).contains(&cells[1]);
+        let fits = |v: usize, w: usize| allowed(v, w) && !(peers && v == w);
+        let mut progress = false;
+        for (a, b) in [(cells[0], cells[1]), (cells[1], cells[0])] {
+            let others = self.cells[b];
+            for v in self.cells[a].iter() {
+                if !others.iter().any(|w| fits(v, w)) {
+                    progress = true;
+                    if !self.eliminate_with(a, v, j) {
+                        return Err(Contradiction);
+                    }
+                }
+            }
+        }
+        Ok(progress)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Mark;
+    use crate::board::SudokuBoard;
+    use crate::sudoku::Sudoku;
+
+    const SOLUTION: &str =
+        "417369825632158947958724316825437169791586432346912758289643571573291684164875293";
+
+    /// Every mark of `kinds` the digits of `SOLUTION` call for, preferring
+    /// the first kind where both apply.
+    fn marks_of(kinds: [Mark; 2]) -> Vec<(usize, usize, Mark)> {
+        let digits: Vec<usize> = SOLUTION.bytes().map(|b| (b - b'0') as usize).collect();
+        let mut marks = Vec::new();
+        for a in 0..81 {
+            let pairs = [(a % 9 < 8).then_some(a + 1), (a < 72).then_some(a + 9)];
+            for b in pairs.into_iter().flatten() {
+                if let Some(mark) = kinds.into_iter().find(|m| m.relates(digits[a], digits[b])) {
+                    marks.push((a, b, mark));
+                }
+            }
+        }
+        marks
+    }
+
+    #[test]
+    fn marks_relate_digits() {
+        assert!(Mark::X.relates(3, 7) && !Mark::X.relates(3, 6));
+        assert!(Mark::V.relates(1, 4) && !Mark::V.relates(2, 4));
+        assert!(Mark::White.relates(5, 4) && !Mark::White.relates(5, 3));
+        assert!(Mark::Black.relates(3, 6) && Mark::Black.relates(8, 4));
+        assert!(!Mark::Black.relates(3, 5));
+    }
+
+    #[test]
+    fn unmarked_pairs_rule_out_relations() {
+        let board = SudokuBoard::new();
+        let puzzle = format!("4{}", ".".repeat(80));
+        // No dot to the right of the 4 or below it: no 3 or 5, no 2 or 8.
+        let mut sudoku = Sudoku::with_dots(&puzzle, &board, &[]).unwrap();
+        assert_eq!(sudoku.reduce(&mut ()), Ok(()));
+        assert_eq!(sudoku.candidates(1), vec![1, 6, 7, 9]);
+        assert_eq!(sudoku.candidates(9), vec![1, 6, 7, 9]);
+        assert_eq!(sudoku.candidates(2).len(), 8);
+
+        // A white dot to its right leaves only the digits next to 4.
+        let mut sudoku = Sudoku::with_dots(&puzzle, &board, &[(0, 1, Mark::White)]).unwrap();
+        assert_eq!(sudoku.reduce(&mut ()), Ok(()));
+        assert_eq!(sudoku.candidates(1), vec![3, 5]);
+        assert_eq!(sudoku.marks().count(), 1);
+    }
+
+    #[test]
+    fn kropki_solves_without_givens() {
+        let board = SudokuBoard::new();
+        let dots = marks_of([Mark::White, Mark::Black]);
+        let sudoku = Sudoku::with_dots(&".".repeat(81), &board, &dots).unwrap();
+        assert_eq!(sudoku.solve().unwrap().to_line(), SOLUTION);
+        assert!(sudoku.has_unique_solution());
+    }
+
+    #[test]
+    fn xv_solutions_follow_the_marks() {
+        let board = SudokuBoard::new();
+        let marks = marks_of([Mark::X, Mark::V]);
+        // Unlike the dots, the marks alone leave a few solutions open.
+        let sudoku = Sudoku::with_xv(&".".repeat(81), &board, &marks).unwrap();
+        let solutions: Vec<Sudoku> = sudoku.solutions().collect();
+        assert_eq!(solutions.len(), 8);
+        assert!(solutions.iter().all(Sudoku::is_valid_solution));
+
+        let puzzle = format!("{}{}", &SOLUTION[..9], ".".repeat(72));
+        let sudoku = Sudoku::with_xv(&puzzle, &board, &marks).unwrap();
+        assert!(sudoku.has_unique_solution());
+        assert_eq!(sudoku.solve().unwrap().to_line(), SOLUTION);
+    }
+}
@@ -0,0 +1,132 @@
+//! The order the search tries the candidates of the cell it branches on.
+
+use super::Sudoku;
+use crate::candidates::Cell;
+use crate::journal::Trail;
+
+/// How the search orders the candidates of the cell it branches on. The
+/// order changes which solution is found first when there are several, and
+/// how much backtracking it takes to get there, never whether one is
+/// found.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ValueOrder {
+    /// Smallest digit first. The default, so that the same puzzle is always
+    /// solved the same way.
+    #[default]
+    Ascending,
+    /// A random order, drawn anew at each branch from a sequence that
+    /// starts at the seed: the same seed always gives the same search.
+    Random(u64),
+    /// The digit left for the fewest undetermined peers first, since trying
+    /// it rules out the fewest candidates around the cell.
+    LeastConstraining,
+}
+
+impl<'a, C: Cell> Sudoku<'a, C> {
+    /// Like [`solve`](Sudoku::solve), with the search trying the candidates
+    /// of each cell it branches on in the given `order`.
+    pub fn solve_with(&self, order: ValueOrder) -> Option<Sudoku<'a, C>> {
+        let mut solution = self.clone();
+        solution.history.clear();
+        solution.order = order;
+        let found = solution.search(&mut Trail::new(()), &mut |_| true);
+        solution.order = self.order;
+        found.then_some(solution)
+    }
+
+    /// The candidates of `k` in the order the search tries them: the first
+    /// entries of the array, as many as the count returned with it.
+    pub(super) fn ordered_values(&mut self, k: usize) -> ([usize; 16], usize) {
+        let mut values = [0; 16];
+        let mut count = 0;
+        for v in self.cells[k].iter() {
+            values[count] = v;
+            count += 1;
+        }
+        match self.order {
+            ValueOrder::Ascending => {}
+            ValueOrder::Random(state) => {
+                let mut state = state;
+                for i in (1..count).rev() {
+                    state = splitmix64(state);
+                    values.swap(i, (state % (i as u64 + 1)) as usize);
+                }
+                self.order = ValueOrder::Random(state);
+            }
+            ValueOrder::LeastConstraining => {
+                let board = self.board;
+                let cells = &self.cells;
+                values[..count].sort_by_key(|v| {
+                    board
+                        .neighbors(k)
+                        .iter()
+                        .filter(|p| cells[**p].len() > 1 && cells[**p].contains(*v))
+                        .count()
+                });
+            }
+        }
+        (values, count)
+    }
+}
+
+/// The next state of a SplitMix64 generator, mixed into a value fit to use
+/// as a random number: cheap, and good enough to shuffle a few digits by.
+fn splitmix64(state: u64) -> u64 {
+    let mut z = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ValueOrder;
+    use crate::board::SudokuBoard;
+    use crate::sudoku::Sudoku;
+
+    const PUZZLE: &str =
+        "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+
+    #[test]
+    fn every_order_solves() {
+        let board = SudokuBoard::new();
+        let sudoku = Sudoku::from(PUZZLE, &board).unwrap();
+        let solution = sudoku.solve().unwrap().to_line();
+        for order in [
+            ValueOrder::Ascending,
+            ValueOrder::Random(1),
+            ValueOrder::Random(2),
+            ValueOrder::LeastConstraining,
+        ] {
+            let solved = sudoku.solve_with(order).unwrap();
+            assert_eq!(solved.to_line(), solution);
+        }
+    }
+
+    #[test]
+    fn orders_pick_among_solutions() {
+        let board = SudokuBoard::new();
+        let empty = Sudoku::empty(&board);
+        let ascending = empty.solve_with(ValueOrder::Ascending).unwrap();
+        assert_eq!(ascending.to_line(), empty.solve().unwrap().to_line());
+        assert_eq!(&ascending.to_line()[..9], "123456789");
+
+        let random = |seed| empty.solve_with(ValueOrder::Random(seed)).unwrap();
+        assert_eq!(random(7).to_line(), random(7).to_line());
+        assert_ne!(random(7).to_line(), random(8).to_line());
+        assert!(random(7).is_valid_solution());
+
+        // Solutions come in the order the search finds them, whatever the
+        // order of the grid.
+        for order in [
+            ValueOrder::Random(7),
+            ValueOrder::LeastConstraining,
+            ValueOrder::Random(8),
+        ] {
+            let mut grid = empty.clone();
+            grid.order = order;
+            let first = grid.solutions().next().unwrap();
+            assert_eq!(first.to_line(), empty.solve_with(order).unwrap().to_line());
+        }
+    }
+}
@@ -0,0 +1,107 @@
+//! Moves made one at a time, as a player would, with each one checked
+//! before it's applied and undoable afterwards.
+
+use super::Sudoku;
+use crate::candidates::Cell;
+use crate::error::MoveError;
+
+impl<'a, C: Cell> Sudoku<'a, C> {
+    /// Places `value` in `cell`, propagating it the way a given is
+    /// propagated when parsing.
+    ///
+    /// Fails, leaving the grid as it was, if the cell already holds a
+    /// digit, if `value` has been ruled out for it, or if placing it would
+    /// leave the grid contradicting itself. Only the cell's own candidates
+    /// are checked before that: a move can be accepted and still be wrong,
+    /// since the grid may have a solution without it.
+    ///
+    /// # Panics
+    ///
+    /// If `cell` isn't on the board.
+    pub fn play(&mut self, cell: usize, value: usize) -> Result<(), MoveError> {
+        if self.value(cell).is_some() {
+            return Err(MoveError::AlreadyFilled { cell });
+        }
+        if !self.cells[cell].contains(value) {
+            return Err(MoveError::NotACandidate { cell, value });
+        }
+        let before = self.cells.clone();
+        if !self.assign(cell, value) {
+            self.cells = before;
+            return Err(MoveError::Contradiction { cell, value });
+        }
+        self.history.push(before);
+        Ok(())
+    }
+
+    /// Takes back the last move [`play`](Sudoku::play) made, along with
+    /// everything it propagated. Returns whether there was a move to take
+    /// back.
+    pub fn undo(&mut self) -> bool {
+        match self.history.pop() {
+            Some(before) => {
+                self.cells = before;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::SudokuBoard;
+    use crate::error::MoveError;
+    use crate::sudoku::Sudoku;
+
+    const PUZZLE: &str =
+        "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+
+    #[test]
+    fn play_checks_and_undoes_moves() {
+        let board = SudokuBoard::new();
+        let start = Sudoku::from(PUZZLE, &board).unwrap();
+        let solution = start.solve().unwrap();
+        let mut game = start.clone();
+
+        assert_eq!(game.play(0, 4), Err(MoveError::AlreadyFilled { cell: 0 }));
+        // Cell 1 shares a row with the given 4 in cell 0.
+        assert_eq!(
+            game.play(1, 4),
+            Err(MoveError::NotACandidate { cell: 1, value: 4 })
+        );
+        assert_eq!(game.to_line(), start.to_line());
+        assert!(!game.undo());
+
+        // Play the solution into the first open cells, one at a time.
+        let mut lines = vec![game.to_line()];
+        for _ in 0..4 {
+            let k = (0..81).find(|k| game.value(*k).is_none()).unwrap();
+            assert_eq!(game.play(k, solution.value(k).unwrap()), Ok(()));
+            lines.push(game.to_line());
+        }
+        assert!(game.filled_count() >= start.filled_count() + 4);
+
+        while let Some(line) = lines.pop() {
+            assert_eq!(game.to_line(), line);
+            assert_eq!(game.undo(), !lines.is_empty());
+        }
+        assert_eq!(game.to_line(), start.to_line());
+    }
+
+    #[test]
+    fn contradicting_moves_leave_the_grid_alone() {
+        // 6 is still a candidate for cell 1, but propagating it runs into
+        // a dead end.
+        let board = SudokuBoard::new();
+        let mut game = Sudoku::from(PUZZLE, &board).unwrap();
+        assert!(game.candidates(1).contains(&6));
+        let before = game.clone();
+        assert_eq!(
+            game.play(1, 6),
+            Err(MoveError::Contradiction { cell: 1, value: 6 })
+        );
+        assert_eq!(game.candidate_counts(), before.candidate_counts());
+        assert!(!game.undo());
+    }
+}
@@ -0,0 +1,322 @@
+use super::Sudoku;
+use crate::candidates::Cell;
+use crate::parse::char_of;
+
+impl<'a, C: Cell> Sudoku<'a, C> {
+    /// The grid the way it's usually printed: one character per cell, the
+    /// digit when it's determined and `.` otherwise, with boxes framed by
+    /// `+---+` borders. Unlike [`Display`](std::fmt::Display), which shows
+    /// every candidate, this is meant for reading puzzles and solutions.
+    pub fn to_pretty(&self) -> String {
+        let n = self.board.box_size();
+        let side = self.board.side();
+        let border = format!("+{}\n", format!("{}+", "-".repeat(n)).repeat(n));
+        let mut out = String::new();
+        for i in 0..side {
+            if i % n == 0 {
+                out.push_str(&border);
+            }
+            for j in 0..side {
+                if j % n == 0 {
+                    out.push('|');
+                }
+                out.push(self.cells[i * side + j].single().map_or('.', char_of));
+            }
+            out.push_str("|\n");
+        }
+        out.push_str(&border);
+        out
+    }
+
+    /// The grid as pencil marks, for working through a puzzle by hand: each
+    /// cell is a small square with room for every digit, showing each of
+    /// its candidates in its own place, `1` at the top left and the largest
+    /// digit at the bottom right, and leaving ruled out ones blank. Boxes
+    /// are framed by `+---+` borders, and the cells inside a box are set
+    /// apart by spaces and blank lines.
+    pub fn to_pencilmarks(&self) -> String {
+        let n = self.board.box_size();
+        let side = self.board.side();
+        let width = n * (n + 1) + 1;
+        let border = format!("+{}\n", format!("{}+", "-".repeat(width)).repeat(n));
+        let blank = format!("|{}\n", format!("{}|", " ".repeat(width)).repeat(n));
+        let mut out = String::new();
+        for i in 0..side {
+            if i % n == 0 {
+                out.push_str(&border);
+            } else {
+                out.push_str(&blank);
+            }
+            for mark_row in 0..n {
+                for j in 0..side {
+                    out.push_str(if j % n == 0 { "| " } else { " " });
+                    let cell = self.cells[i * side + j];
+                    for v in mark_row * n + 1..=mark_row * n + n {
+                        out.push(if cell.contains(v) { char_of(v) } else { ' ' });
+                    }
+                    if j % n == n - 1 {
+                        out.push(' ');
+                    }
+                }
+                out.push_str("|\n");
+            }
+        }
+        out.push_str(&border);
+        out
+    }
+
+    /// The grid as an SVG image, for embedding in a web page or printing.
+    /// Boxes are framed by thicker lines than cells, and determined cells
+    /// show their digit, black for the givens and blue for the digits the
+    /// solver found. Every other cell shows its candidates as small gray
+    /// pencil marks, laid out like [`to_pencilmarks`](Sudoku::to_pencilmarks)
+    /// lays them out.
+    pub fn to_svg(&self) -> String {
+        // The side of a cell and the margin around the grid, in pixels.
+        const CELL: usize = 40;
+        const MARGIN: usize = 2;
+        let n = self.board.box_size();
+        let side = self.board.side();
+        let size = side * CELL + 2 * MARGIN;
+        let mut out = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{0}\" \
+             viewBox=\"0 0 {0} {0}\">\n<rect width=\"{0}\" height=\"{0}\" fill=\"white\"/>\n",
+            size
+        );
+        for i in 0..=side {
+            let at = MARGIN + i * CELL;
+            let (from, to) = (MARGIN, MARGIN + side * CELL);
+            let width = if i % n == 0 { 3 } else { 1 };
+            for (x1, y1, x2, y2) in [(at, from, at, to), (from, at, to, at)] {
+                out.push_str(&format!(
+                    "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"black\" \
+                     stroke-width=\"{}\"/>\n",
+                    x1, y1, x2, y2, width
+                ));
+            }
+        }
+        let text = |out: &mut String, x: usize, y: usize, size: usize, fill: &str, v: usize| {
+            out.push_str(&format!(
+                "<text x=\"{}\" y=\"{}\" font-size=\"{}\" fill=\"{}\" \
+                 text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>\n",
+                x,
+                y,
+                size,
+                fill,
+                char_of(v)
+            ));
+        };
+        for (k, cell) in self.cells.iter().enumerate() {
+            let (left, top) = (MARGIN + (k % side) * CELL, MARGIN + (k / side) * CELL);
+            if let Some(v) = cell.single() {
+                let fill = if self.is_given(k) { "black" } else { "#1a5fb4" };
+                text(
+                    &mut out,
+                    left + CELL / 2,
+                    top + CELL / 2,
+                    CELL * 3 / 5,
+                    fill,
+                    v,
+                );
+                continue;
+            }
+            let mark = CELL / n;
+            for v in cell.iter() {
+                let (r, c) = ((v - 1) / n, (v - 1) % n);
+                let (x, y) = (left + c * mark + mark / 2, top + r * mark + mark / 2);
+                text(&mut out, x, y, mark * 4 / 5, "gray", v);
+            }
+        }
+        out.push_str("</svg>\n");
+        out
+    }
+
+    /// The grid as a SAT problem in DIMACS CNF, for external SAT solvers.
+    ///
+    /// There's a variable for every cell and digit, true when the cell
+    /// holds the digit: cell `k`, counting from 0 in row-major order, and
+    /// digit `v`, from 1, make variable `k * side + v`, so a 9x9 grid has
+    /// 729 of them, `81 * row + 9 * column + v`. The clauses say that every
+    /// cell holds some digit but no two, that every unit of the board has
+    /// every digit somewhere, and that no two peers hold the same digit,
+    /// followed by one clause with a single variable for every determined
+    /// cell. The constraints of variants, cages and thermometers, are left
+    /// out.
+    pub fn to_dimacs(&self) -> String {
+        let board = self.board;
+        let side = board.side();
+        let var = |k: usize, v: usize| k * side + v;
+        let mut clauses: Vec<Vec<isize>> = Vec::new();
+        for k in 0..self.cells.len() {
+            clauses.push((1..=side).map(|v| var(k, v) as isize).collect());
+            for v in 1..=side {
+                for w in v + 1..=side {
+                    clauses.push(vec![-(var(k, v) as isize), -(var(k, w) as isize)]);
+                }
+            }
+        }
+        for u in 0..board.unit_count() {
+            let unit = board.cells_in_unit(u);
+            for v in 1..=side {
+                clauses.push(unit.iter().map(|k| var(*k, v) as isize).collect());
+            }
+        }
+        for k in 0..self.cells.len() {
+            let mut peers: Vec<usize> = board
+                .neighbors(k)
+                .iter()
+                .copied()
+                .filter(|n| *n > k)
+                .collect();
+            peers.sort_unstable();
+            for n in peers {
+                for v in 1..=side {
+                    clauses.push(vec![-(var(k, v) as isize), -(var(n, v) as isize)]);
+                }
+            }
+        }
+        for (k, cell) in self.cells.iter().enumerate() {
+            if let Some(v) = cell.single() {
+                clauses.push(vec![var(k, v) as isize]);
+            }
+        }
+        let mut out = format!("p cnf {} {}\n", self.cells.len() * side, clauses.len());
+        for clause in clauses {
+            for lit in clause {
+                out.push_str(&lit.to_string());
+                out.push(' ');
+            }
+            out.push_str("0\n");
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::SudokuBoard;
+    use crate::sudoku::Sudoku;
+
+    #[test]
+    fn pretty_grid() {
+        let board = SudokuBoard::with_box_size(2);
+        let sudoku = Sudoku::from("..3.....1.......", &board).unwrap();
+        assert_eq!(
+            sudoku.to_pretty(),
+            "+--+--+\n|..|3.|\n|..|..|\n+--+--+\n|1.|..|\n|..|..|\n+--+--+\n"
+        );
+
+        let board = SudokuBoard::new();
+        let s = "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+        let pretty = Sudoku::from(s, &board)
+            .unwrap()
+            .solve()
+            .unwrap()
+            .to_pretty();
+        let lines: Vec<&str> = pretty.lines().collect();
+        assert_eq!(lines.len(), 13);
+        assert_eq!(lines[0], "+---+---+---+");
+        assert_eq!(lines[1], "|417|369|825|");
+        assert_eq!(lines[4], "+---+---+---+");
+    }
+
+    #[test]
+    fn pencilmarks_grid() {
+        let board = SudokuBoard::with_box_size(2);
+        let sudoku = Sudoku::from("..3.....1.......", &board).unwrap();
+        let expected = [
+            "+-------+-------+",
+            "|  2 12 |    12 |",
+            "|  4  4 | 3   4 |",
+            "|       |       |",
+            "|  2 12 | 12 12 |",
+            "| 34 34 |  4  4 |",
+            "+-------+-------+",
+            "| 1   2 |  2  2 |",
+            "|    34 |  4 34 |",
+            "|       |       |",
+            "|  2  2 | 12 12 |",
+            "| 34 34 |  4 34 |",
+            "+-------+-------+",
+        ];
+        assert_eq!(sudoku.to_pencilmarks(), expected.join("\n") + "\n");
+
+        let board = SudokuBoard::new();
+        let s = "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+        let marks = Sudoku::from(s, &board).unwrap().to_pencilmarks();
+        let lines: Vec<&str> = marks.lines().collect();
+        assert_eq!(lines.len(), 37);
+        assert!(lines.iter().all(|l| l.len() == 43));
+        assert_eq!(lines[0], format!("+{}", "-------------+".repeat(3)));
+        // The given 4 of the first cell, in the middle row of its square.
+        assert_eq!(&lines[1][..6], "|     ");
+        assert_eq!(&lines[2][..6], "| 4   ");
+        assert_eq!(&lines[3][..6], "|     ");
+    }
+
+    #[test]
+    fn dimacs_encoding() {
+        let board = SudokuBoard::new();
+        let s = "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+        let sudoku = Sudoku::from(s, &board).unwrap();
+        let cnf = sudoku.to_dimacs();
+        let mut lines = cnf.lines();
+        // 81 cells with some digit, 81 * 36 pairs of digits, 27 * 9 digits
+        // to place in the units and 810 * 9 for the pairs of peers, then
+        // the determined cells.
+        let clauses = 81 + 81 * 36 + 27 * 9 + 810 * 9 + sudoku.filled_count();
+        assert_eq!(
+            lines.next(),
+            Some(format!("p cnf 729 {}", clauses).as_str())
+        );
+        let clauses: Vec<Vec<isize>> = lines
+            .map(|l| l.split(' ').map(|lit| lit.parse().unwrap()).collect())
+            .collect();
+        assert!(clauses.iter().all(|c| c.last() == Some(&0)));
+        // The top left 4, then the top right 5.
+        assert!(clauses.contains(&vec![4, 0]));
+        assert!(clauses.contains(&vec![8 * 9 + 5, 0]));
+
+        // The solution satisfies them all, and a grid off by a swap doesn't.
+        let solution = sudoku.solve().unwrap().to_bytes().unwrap();
+        let satisfies = |digits: &[u8; 81]| {
+            clauses.iter().all(|c| {
+                c.iter().filter(|lit| **lit != 0).any(|lit| {
+                    let k = (lit.unsigned_abs() - 1) / 9;
+                    let v = (lit.unsigned_abs() - 1) % 9 + 1;
+                    (digits[k] as usize == v) == (*lit > 0)
+                })
+            })
+        };
+        assert!(satisfies(&solution));
+        let mut swapped = solution;
+        swapped.swap(1, 2);
+        assert!(!satisfies(&swapped));
+    }
+
+    #[test]
+    fn svg_grid() {
+        let board = SudokuBoard::new();
+        let s = "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+        let sudoku = Sudoku::from(s, &board).unwrap();
+        let svg = sudoku.to_svg();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"364\""));
+        assert!(svg.ends_with("</svg>\n"));
+        // Ten lines across and ten down, four of each thick.
+        assert_eq!(svg.matches("<line ").count(), 20);
+        assert_eq!(svg.matches("stroke-width=\"3\"").count(), 8);
+        let texts = |fill: &str| svg.matches(&format!("fill=\"{}\"", fill)).count();
+        assert_eq!(texts("black"), 17);
+        assert_eq!(texts("#1a5fb4"), sudoku.filled_count() - 17);
+        let marks: usize = (0..81)
+            .filter(|k| sudoku.value(*k).is_none())
+            .map(|k| sudoku.candidates(k).len())
+            .sum();
+        assert_eq!(texts("gray"), marks);
+
+        let solved = sudoku.solve().unwrap().to_svg();
+        assert_eq!(solved.matches("fill=\"black\"").count(), 17);
+        assert_eq!(solved.matches("fill=\"#1a5fb4\"").count(), 64);
+        assert!(!solved.contains("fill=\"gray\""));
+    }
+}
@@ -0,0 +1,325 @@
+//! Samurai sudoku: five 9x9 grids, one in the middle sharing a corner box
+//! with each of the other four.
+
+use super::Sudoku;
+use crate::board::SudokuBoard;
+use crate::candidates::Cell;
+use crate::error::{Contradiction, ParseError};
+use crate::parse::{char_of, digit_of, EmptyChars};
+use core::fmt;
+
+/// The width and height of the layout the five grids are drawn on.
+const SIDE: usize = 21;
+
+/// The top left corner of each grid on the layout: top left, top right,
+/// middle, bottom left and bottom right.
+const CORNERS: [(usize, usize); 5] = [(0, 0), (0, 12), (6, 6), (12, 0), (12, 12)];
+
+/// The number of cells of the layout that belong to some grid: five grids
+/// of 81, less the 9 of each of the four shared boxes counted twice.
+const CELLS: usize = 5 * 81 - 4 * 9;
+
+/// Where each cell of the puzzle lies, in reading order over the layout:
+/// the grids holding it, with its index in each. Cells of the shared boxes
+/// lie in two grids, every other cell in one.
+fn places() -> impl Iterator<Item = Vec<(usize, usize)>> {
+    (0..SIDE * SIDE)
+        .map(|p| {
+            let (r, c) = (p / SIDE, p % SIDE);
+            CORNERS
+                .iter()
+                .enumerate()
+                .filter(|(_, (top, left))| {
+                    (*top..top + 9).contains(&r) && (*left..left + 9).contains(&c)
+                })
+                .map(|(g, (top, left))| (g, (r - top) * 9 + c - left))
+                .collect::<Vec<_>>()
+        })
+        .filter(|places| !places.is_empty())
+}
+
+/// A samurai sudoku: five standard grids, the middle one sharing its corner
+/// boxes with the corner boxes of the other four that face it.
+///
+/// Each grid keeps its own candidates and propagates them on the standard
+/// board, the way a lone [`Sudoku`] does. The shared cells link the grids:
+/// a digit ruled out for a shared cell in one grid is ruled out in the
+/// other too, and the grids are kept in step that way throughout the
+/// search.
+#[derive(Clone)]
+pub struct SamuraiSudoku {
+    grids: [Sudoku<'static>; 5],
+}
+
+impl SamuraiSudoku {
+    /// Parses a puzzle, one character per cell in reading order over the
+    /// 21x21 layout, skipping the gaps between the grids. Characters are
+    /// read as [`Sudoku::from`] reads them, so the puzzle can be a single
+    /// line of 369 cells, or laid out as [`Display`](fmt::Display) draws it,
+    /// with spaces in the gaps.
+    ///
+    /// Fails if the input doesn't describe exactly one character per cell,
+    /// or if some given contradicts the ones before it, the cell counting
+    /// in the same reading order.
+    pub fn from(s: &str) -> Result<SamuraiSudoku, ParseError> {
+        let empty = EmptyChars::default();
+        let digits: Vec<usize> = s
+            .chars()
+            .filter_map(|c| digit_of(c, 9).or_else(|| empty.contains(c).then_some(0)))
+            .collect();
+        if digits.len() != CELLS {
+            return Err(ParseError::BadLength {
+                expected: CELLS,
+                got: digits.len(),
+            });
+        }
+        let board = SudokuBoard::standard();
+        let mut samurai = SamuraiSudoku {
+            grids: std::array::from_fn(|_| Sudoku::empty(board)),
+        };
+        for (cell, (v, places)) in digits.into_iter().zip(places()).enumerate() {
+            if v == 0 {
+                continue;
+            }
+            for (g, k) in places {
+                let grid = &mut samurai.grids[g];
+                grid.givens[k / 64] |= 1 << (k % 64);
+                if !grid.assign(k, v) {
+                    return Err(ParseError::Contradiction { cell });
+                }
+            }
+        }
+        for grid in &mut samurai.grids {
+            grid.clues = grid.filled_count();
+        }
+        Ok(samurai)
+    }
+
+    /// One of the five grids: `0` is the top left one, `1` the top right,
+    /// `2` the middle, `3` the bottom left and `4` the bottom right.
+    ///
+    /// # Panics
+    ///
+    /// If `i` isn't below 5.
+    #[inline]
+    pub fn grid(&self, i: usize) -> &Sudoku<'static> {
+        &self.grids[i]
+    }
+
+    /// Whether every cell of every grid has been narrowed down to a single
+    /// digit.
+    pub fn is_solved(&self) -> bool {
+        self.grids.iter().all(Sudoku::is_solved)
+    }
+
+    /// The puzzle as a single line, one character per cell in the reading
+    /// order [`SamuraiSudoku::from`] takes: the digit for every determined
+    /// cell and `.` for the rest.
+    pub fn to_line(&self) -> String {
+        places().map(|places| self.char_at(places[0])).collect()
+    }
+
+    /// The solution, if there is one.
+    pub fn solve(&self) -> Option<SamuraiSudoku> {
+        let mut solution = None;
+        self.clone().search(&mut |s| {
+            solution = Some(s.clone());
+            true
+        });
+        solution
+    }
+
+    /// Counts the solutions, stopping once `cap` are found.
+    pub fn count_solutions(&self, cap: usize) -> usize {
+        let mut count = 0;
+        if cap > 0 {
+            self.clone().search(&mut |_| {
+                count += 1;
+                count >= cap
+            });
+        }
+        count
+    }
+
+    fn char_at(&self, (g, k): (usize, usize)) -> char {
+        self.grids[g].cells[k].single().map_or('.', char_of)
+    }
+
+    /// Rules out, for each shared cell, the digits ruled out for it in the
+    /// other grid that holds it. Returns whether anything was ruled out.
+    fn link(&mut self) -> Result<bool, Contradiction> {
+        let mut progress = false;
+        for places in places().filter(|places| places.len() == 2) {
+            for (from, to) in [(places[0], places[1]), (places[1], places[0])] {
+                let kept = self.grids[from.0].cells[from.1];
+                let grid = &mut self.grids[to.0];
+                for v in grid.cells[to.1].iter().filter(|v| !kept.contains(*v)) {
+                    progress = true;
+                    if !grid.eliminate(to.1, v) {
+                        return Err(Contradiction);
+                    }
+                }
+            }
+        }
+        Ok(progress)
+    }
+
+    /// Applies each grid's techniques and the links between the grids,
+    /// until neither rules anything else out.
+    fn reduce(&mut self) -> Result<(), Contradiction> {
+        loop {
+            for grid in &mut self.grids {
+                grid.reduce(&mut ())?;
+            }
+            if !self.link()? {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Depth-first search over the completions of the puzzle, calling
+    /// `found` on each until it returns `true`, after the one
+    /// [`Sudoku::search`] does. The grids are linked, so a node takes a
+    /// copy of all five rather than rolling back any one of them.
+    fn search<F: FnMut(&SamuraiSudoku) -> bool>(mut self, found: &mut F) -> bool {
+        if self.reduce().is_err() {
+            return false;
+        }
+        if self.is_solved() {
+            return found(&self);
+        }
+        let (g, k) = (0..5)
+            .filter(|g| !self.grids[*g].is_solved())
+            .map(|g| (g, self.grids[g].select_cell()))
+            .min_by_key(|(g, k)| self.grids[*g].cells[*k].len())
+            .unwrap();
+        for v in self.grids[g].cells[k].iter() {
+            let mut guess = self.clone();
+            if guess.grids[g].assign(k, v) && guess.search(found) {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl fmt::Display for SamuraiSudoku {
+    /// The layout, one row per line, with the digit for every determined
+    /// cell, `.` for the rest and spaces in the gaps between the grids.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut places = places();
+        for r in 0..SIDE {
+            let line: String = (0..SIDE)
+                .map(|c| {
+                    let inside = CORNERS.iter().any(|(top, left)| {
+                        (*top..top + 9).contains(&r) && (*left..left + 9).contains(&c)
+                    });
+                    match inside {
+                        true => self.char_at(places.next().unwrap()[0]),
+                        false => ' ',
+                    }
+                })
+                .collect();
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SamuraiSudoku, CELLS};
+    use crate::board::SudokuBoard;
+    use crate::error::ParseError;
+    use crate::sudoku::Sudoku;
+
+    /// A puzzle with a unique solution, laid out as it's drawn.
+    const PUZZLE: [&str; 21] = [
+        "1...56.8.   ..1..6...",
+        "4..7...2.   ........4",
+        "..9...4..   9...3..6.",
+        "...8.4...   ...9..5..",
+        ".7...56..   ..5...49.",
+        "93.....4.   ..7..4..1",
+        "6.....5......3...58.7",
+        "......8.........21..6",
+        "..8......2.........52",
+        "      .....2...",
+        "      ...8...6.",
+        "      .9...3..7",
+        ".........9......3.6.9",
+        "..81............8..3.",
+        "..9..6...4..6......5.",
+        "..1..2..7   ...8.....",
+        ".6..9...5   ..7...8..",
+        "8...7..6.   .69..4..7",
+        "...5..2..   .4..61..2",
+        "..5..79..   5......1.",
+        "..7.....4   ......7..",
+    ];
+
+    #[test]
+    fn links_decide_the_grids() {
+        let puzzle = SamuraiSudoku::from(&PUZZLE.join("\n")).unwrap();
+        assert_eq!(puzzle.count_solutions(2), 1);
+        let solved = puzzle.solve().unwrap();
+        assert!((0..5).all(|g| solved.grid(g).is_valid_solution()));
+        assert_eq!(solved.to_string().lines().count(), 21);
+
+        // On its own, the middle grid has more than one solution.
+        let middle: String = PUZZLE[6..15].iter().map(|r| &r[6..15]).collect();
+        let middle = Sudoku::from(&middle, SudokuBoard::standard()).unwrap();
+        assert_eq!(middle.count_solutions(2), 2);
+        let line = solved.grid(2).to_line();
+        assert!(middle
+            .to_line()
+            .chars()
+            .zip(line.chars())
+            .all(|(m, s)| m == '.' || m == s));
+    }
+
+    #[test]
+    fn empty_puzzle_solves_consistently() {
+        let empty = SamuraiSudoku::from(&".".repeat(CELLS)).unwrap();
+        let solved = empty.solve().unwrap();
+        assert!(solved.is_solved());
+        assert!((0..5).all(|g| solved.grid(g).is_valid_solution()));
+        // The middle grid's corner boxes are the facing boxes of the others.
+        let middle = solved.grid(2).to_line();
+        let top_left = solved.grid(0).to_line();
+        for i in 0..3 {
+            assert_eq!(middle[i * 9..i * 9 + 3], top_left[60 + i * 9..63 + i * 9]);
+        }
+        let line = solved.to_line();
+        assert_eq!(line.len(), CELLS);
+        assert_eq!(
+            SamuraiSudoku::from(&solved.to_string()).unwrap().to_line(),
+            line
+        );
+    }
+
+    #[test]
+    fn parsing_checks_length_and_givens() {
+        assert_eq!(
+            SamuraiSudoku::from("123").err(),
+            Some(ParseError::BadLength {
+                expected: CELLS,
+                got: 3
+            })
+        );
+        assert_eq!(
+            SamuraiSudoku::from(&format!("11{}", ".".repeat(CELLS - 2))).err(),
+            Some(ParseError::Contradiction { cell: 1 })
+        );
+        // Row 6 of the layout starts the middle grid, whose first box is
+        // shared with the top left grid: a 1 in each is a clash in the
+        // middle grid's first row.
+        let mut line = vec!['.'; CELLS];
+        line[108 + 6] = '1';
+        line[108 + 9] = '1';
+        assert_eq!(
+            SamuraiSudoku::from(&line.iter().collect::<String>()).err(),
+            Some(ParseError::Contradiction { cell: 117 })
+        );
+    }
+}
@@ -0,0 +1,972 @@
+//! Solving techniques as standalone passes over the whole grid.
+//!
+//! Each pass returns whether it removed any candidate, or a
+//! [`Contradiction`] if it found the grid unsolvable along the way.
+
+use super::Sudoku;
+use crate::candidates::{Candidates, Cell};
+use crate::error::Contradiction;
+use crate::journal::Journal;
+use std::ops::Range;
+
+/// A deduction the search can make before resorting to a guess, on top of
+/// the naked and hidden singles propagation always finds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Technique {
+    /// Two cells of a unit with the same two candidates: no other cell of
+    /// the unit can have either.
+    NakedPairs,
+    /// A digit confined, within a box, to one row or column: no other cell
+    /// of that row or column can have it.
+    Pointing,
+    /// A digit confined, within a row or column, to one box: no other cell
+    /// of that box can have it.
+    Claiming,
+    /// A digit confined, within two rows, to the same two columns: no
+    /// other cell of those columns can have it. Likewise with rows and
+    /// columns swapped.
+    XWing,
+    /// A digit confined, within three rows, to the same three columns: no
+    /// other cell of those columns can have it. Likewise with rows and
+    /// columns swapped. Each row may hold the digit in just two of them.
+    Swordfish,
+    /// Two digits confined, within a unit, to the same two cells: neither
+    /// cell can have any other digit.
+    HiddenPairs,
+    /// Three digits confined, within a unit, to the same three cells: none
+    /// of them can have any other digit.
+    HiddenTriples,
+    /// Cells linked in a chain by units each holding a digit in just two
+    /// places, which take the digit in turns. If two cells taking it on the
+    /// same turn are peers, no cell taking it on that turn can have it.
+    /// Otherwise, a cell outside the chain that is a peer of cells on both
+    /// turns can't have it.
+    Coloring,
+}
+
+impl Technique {
+    const ALL: [Technique; 8] = [
+        Technique::NakedPairs,
+        Technique::Pointing,
+        Technique::Claiming,
+        Technique::XWing,
+        Technique::Swordfish,
+        Technique::HiddenPairs,
+        Technique::HiddenTriples,
+        Technique::Coloring,
+    ];
+}
+
+/// A set of [`Technique`]s. The default is all of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Techniques(u16);
+
+impl Techniques {
+    /// No techniques: the search relies on singles and guessing alone.
+    pub const NONE: Techniques = Techniques(0);
+
+    /// Every technique.
+    pub fn all() -> Techniques {
+        Technique::ALL
+            .iter()
+            .fold(Techniques::NONE, |ts, t| ts.with(*t))
+    }
+
+    #[inline]
+    pub fn contains(self, t: Technique) -> bool {
+        self.0 & (1 << t as u16) != 0
+    }
+
+    /// This set, plus `t`.
+    #[inline]
+    pub fn with(self, t: Technique) -> Techniques {
+        Techniques(self.0 | 1 << t as u16)
+    }
+
+    /// This set, minus `t`.
+    #[inline]
+    pub fn without(self, t: Technique) -> Techniques {
+        Techniques(self.0 & !(1 << t as u16))
+    }
+}
+
+impl Default for Techniques {
+    fn default() -> Self {
+        Techniques::all()
+    }
+}
+
+/// How far the techniques alone take a grid, without guessing.
+#[derive(Clone, PartialEq, Eq)]
+pub enum LogicalResult<'a, C: Cell = Candidates> {
+    /// Every cell determined: the solution.
+    Solved(Sudoku<'a, C>),
+    /// Where the techniques run out, with cells left undetermined.
+    Stuck(Sudoku<'a, C>),
+    /// The techniques found the grid has no solution.
+    Contradiction,
+}
+
+impl<'a, C: Cell> Sudoku<'a, C> {
+    /// Solves this grid with propagation, its techniques and its
+    /// constraints only, over and over until none of them makes progress,
+    /// never guessing. A puzzle they solve is one a person can solve by the
+    /// same deductions, which grades it better than counting the guesses of
+    /// [`solve`](Sudoku::solve).
+    pub fn solve_logical_only(&self) -> LogicalResult<'a, C> {
+        let mut grid = self.clone();
+        grid.history.clear();
+        match grid.reduce(&mut ()) {
+            Err(Contradiction) => LogicalResult::Contradiction,
+            Ok(()) if grid.is_solved() => LogicalResult::Solved(grid),
+            Ok(()) => LogicalResult::Stuck(grid),
+        }
+    }
+
+    /// Applies the grid's techniques, and its constraints if it has any, over
+    /// and over until none of them makes progress.
+    pub(crate) fn reduce<J: Journal>(&mut self, j: &mut J) -> Result<(), Contradiction> {
+        let ts = self.techniques;
+        loop {
+            let mut progress = false;
+            if ts.contains(Technique::NakedPairs) {
+                progress |= self.naked_pairs_pass(j)?;
+            }
+            if ts.contains(Technique::Pointing) {
+                progress |= self.pointing_pass(j)?;
+            }
+            if ts.contains(Technique::Claiming) {
+                progress |= self.claiming_pass(j)?;
+            }
+            if ts.contains(Technique::XWing) {
+                progress |= self.fish_pass(2, j)?;
+            }
+            if ts.contains(Technique::Swordfish) {
+                progress |= self.fish_pass(3, j)?;
+            }
+            progress |= self.constraints_pass(j)?;
+            // Hidden subsets take the longest to look for, so only once
+            // everything else is stuck.
+            if !progress && ts.contains(Technique::HiddenPairs) {
+                progress |= self.hidden_subsets_pass(2, j)?;
+            }
+            if !progress && ts.contains(Technique::HiddenTriples) {
+                progress |= self.hidden_subsets_pass(3, j)?;
+            }
+            if !progress && ts.contains(Technique::Coloring) {
+                progress |= self.coloring_pass(j)?;
+            }
+            if !progress {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Clears every determined digit out of its cell's peers.
+    ///
+    /// Propagation already does this as cells get determined, so on a grid
+    /// built by this crate it finds nothing left to do.
+    pub fn propagate_naked_singles(&mut self) -> Result<bool, Contradiction> {
+        self.naked_singles_pass(&mut ())
+    }
+
+    /// Places every digit that has a single cell left in some unit.
+    ///
+    /// As with naked singles, propagation already does this as candidates go
+    /// away.
+    pub fn propagate_hidden_singles(&mut self) -> Result<bool, Contradiction> {
+        self.hidden_singles_pass(&mut ())
+    }
+
+    /// Applies [`Technique::NakedPairs`] across every unit.
+    pub fn eliminate_naked_pairs(&mut self) -> Result<bool, Contradiction> {
+        self.naked_pairs_pass(&mut ())
+    }
+
+    /// Applies [`Technique::Pointing`] across every box.
+    pub fn eliminate_pointing(&mut self) -> Result<bool, Contradiction> {
+        self.pointing_pass(&mut ())
+    }
+
+    /// Applies [`Technique::Claiming`] across every row and column.
+    pub fn eliminate_claiming(&mut self) -> Result<bool, Contradiction> {
+        self.claiming_pass(&mut ())
+    }
+
+    /// Applies [`Technique::XWing`] across every pair of rows, and every
+    /// pair of columns.
+    pub fn eliminate_x_wing(&mut self) -> Result<bool, Contradiction> {
+        self.fish_pass(2, &mut ())
+    }
+
+    /// Applies [`Technique::Swordfish`] across every three rows, and every
+    /// three columns.
+    pub fn eliminate_swordfish(&mut self) -> Result<bool, Contradiction> {
+        self.fish_pass(3, &mut ())
+    }
+
+    /// Applies [`Technique::HiddenPairs`] across every unit when `size` is
+    /// 2, and [`Technique::HiddenTriples`] when it's 3.
+    ///
+    /// # Panics
+    ///
+    /// If `size` is neither 2 nor 3.
+    pub fn eliminate_hidden_subsets(&mut self, size: usize) -> Result<bool, Contradiction> {
+        assert!(
+            size == 2 || size == 3,
+            "no hidden subsets of {} digits",
+            size
+        );
+        self.hidden_subsets_pass(size, &mut ())
+    }
+
+    /// Applies [`Technique::Coloring`] for every digit.
+    pub fn eliminate_coloring(&mut self) -> Result<bool, Contradiction> {
+        self.coloring_pass(&mut ())
+    }
+
+    fn pointing_pass<J: Journal>(&mut self, j: &mut J) -> Result<bool, Contradiction> {
+        let side = self.board.side();
+        self.confined_pass(2 * side..3 * side, j)
+    }
+
+    fn claiming_pass<J: Journal>(&mut self, j: &mut J) -> Result<bool, Contradiction> {
+        let side = self.board.side();
+        self.confined_pass(0..2 * side, j)
+    }
+
+    /// For each unit of `units` and each digit whose places in it all lie in
+    /// some other unit, rules the digit out for the rest of that other unit.
+    fn confined_pass<J: Journal>(
+        &mut self,
+        units: Range<usize>,
+        j: &mut J,
+    ) -> Result<bool, Contradiction> {
+        let mut progress = false;
+        let board = self.board;
+        for u in units {
+            let unit = board.cells_in_unit(u);
+            for v in 1..=board.side() {
+                // The places of `v` in the unit, by position, as a bit set
+                // so that eliminating doesn't fight over borrowing them.
+                let places = unit
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, p)| self.cells[**p].contains(v))
+                    .fold(0u64, |set, (i, _)| set | 1 << i);
+                if places == 0 {
+                    return Err(Contradiction);
+                }
+                if places.count_ones() == 1 {
+                    continue;
+                }
+                let first = unit[places.trailing_zeros() as usize];
+                for &w in board.units_for(first).iter().filter(|w| **w != u) {
+                    let line = board.cells_in_unit(w);
+                    let confined = (0..unit.len())
+                        .filter(|i| places & 1 << i != 0)
+                        .all(|i| line.contains(&unit[i]));
+                    if !confined {
+                        continue;
+                    }
+                    for &c in line.iter().filter(|c| !unit.contains(c)) {
+                        if self.cells[c].contains(v) {
+                            progress = true;
+                            if !self.eliminate_with(c, v, j) {
+                                return Err(Contradiction);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(progress)
+    }
+
+    /// For each digit, and each `size` rows holding it in `size` columns
+    /// between them, rules the digit out for the rest of those columns: the
+    /// rows need a cell of each. Likewise with rows and columns swapped.
+    ///
+    /// A row may hold the digit in fewer than `size` of the columns, as
+    /// long as it has two places left: one place is a hidden single, not
+    /// part of a fish. Should the rows need more columns than they hold
+    /// the digit in between them, that's a contradiction.
+    fn fish_pass<J: Journal>(&mut self, size: usize, j: &mut J) -> Result<bool, Contradiction> {
+        debug_assert!((2..=4).contains(&size));
+        let mut progress = false;
+        let board = self.board;
+        let side = board.side();
+        // Rows as base lines and columns as covers, then the other way.
+        for (base, cover) in [(0, side), (side, 0)] {
+            // The places of each digit in each base line, by position along
+            // the line, all gathered in one sweep. Eliminating only ever
+            // shrinks them, so a fish found from stale places still holds.
+            let mut places = [[0u32; 17]; 16];
+            for (l, line) in places[..side].iter_mut().enumerate() {
+                for (i, &p) in board.cells_in_unit(base + l).iter().enumerate() {
+                    for v in self.cells[p].iter() {
+                        line[v] |= 1 << i;
+                    }
+                }
+            }
+            for v in 1..=side {
+                // The base lines that could be part of a fish.
+                let mut lines = [(0, 0u32); 16];
+                let mut count = 0;
+                for (l, set) in places[..side].iter().map(|line| line[v]).enumerate() {
+                    if (2..=size).contains(&(set.count_ones() as usize)) {
+                        lines[count] = (l, set);
+                        count += 1;
+                    }
+                }
+                if count < size {
+                    continue;
+                }
+                // Every choice of `size` of them, in increasing order.
+                let mut chosen = [0, 1, 2, 3];
+                let chosen = &mut chosen[..size];
+                loop {
+                    let union = chosen.iter().fold(0, |set, c| set | lines[*c].1);
+                    if (union.count_ones() as usize) < size {
+                        return Err(Contradiction);
+                    }
+                    if union.count_ones() as usize == size {
+                        for i in (0..side).filter(|i| union & 1 << i != 0) {
+                            let covered = board.cells_in_unit(cover + i);
+                            for (l, &c) in covered.iter().enumerate() {
+                                if chosen.iter().any(|k| lines[*k].0 == l)
+                                    || !self.cells[c].contains(v)
+                                {
+                                    continue;
+                                }
+                                progress = true;
+                                if !self.eliminate_with(c, v, j) {
+                                    return Err(Contradiction);
+                                }
+                            }
+                        }
+                    }
+                    if !next_choice(chosen, count) {
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(progress)
+    }
+
+    /// For each unit, and each `size` digits whose places in it lie in
+    /// `size` cells between them, rules every other digit out for those
+    /// cells: the digits need a cell each.
+    fn hidden_subsets_pass<J: Journal>(
+        &mut self,
+        size: usize,
+        j: &mut J,
+    ) -> Result<bool, Contradiction> {
+        let mut progress = false;
+        let board = self.board;
+        for u in 0..board.unit_count() {
+            let unit = board.cells_in_unit(u);
+            // The places of each digit in the unit, by position.
+            let mut places = [0u32; 17];
+            for (i, &p) in unit.iter().enumerate() {
+                for v in self.cells[p].iter() {
+                    places[v] |= 1 << i;
+                }
+            }
+            // The digits that could be part of a subset. A digit with one
+            // place left is a hidden single rather than part of one.
+            let mut digits = [(0, 0u32); 16];
+            let mut count = 0;
+            for (v, &set) in places.iter().enumerate().take(board.side() + 1).skip(1) {
+                if set == 0 {
+                    return Err(Contradiction);
+                }
+                if (2..=size).contains(&(set.count_ones() as usize)) {
+                    digits[count] = (v, set);
+                    count += 1;
+                }
+            }
+            if count < size {
+                continue;
+            }
+            // Every choice of `size` of them, in increasing order.
+            let mut chosen = [0, 1, 2];
+            let chosen = &mut chosen[..size];
+            loop {
+                let union = chosen.iter().fold(0, |set, c| set | digits[*c].1);
+                if union.count_ones() as usize == size {
+                    for i in (0..unit.len()).filter(|i| union & 1 << i != 0) {
+                        let c = unit[i];
+                        let others = self.cells[c]
+                            .iter()
+                            .filter(|v| chosen.iter().all(|k| digits[*k].0 != *v));
+                        for v in others {
+                            progress = true;
+                            if !self.eliminate_with(c, v, j) {
+                                return Err(Contradiction);
+                            }
+                        }
+                    }
+                }
+                if !next_choice(chosen, count) {
+                    break;
+                }
+            }
+        }
+        Ok(progress)
+    }
+
+    /// For each digit, colors the chains of cells linked by units holding
+    /// it in two places, alternating between two colors along each chain,
+    /// and rules it out for one color of a chain when two cells of that
+    /// color are peers, or else for every cell outside the chain that is a
+    /// peer of cells of both colors.
+    fn coloring_pass<J: Journal>(&mut self, j: &mut J) -> Result<bool, Contradiction> {
+        let mut progress = false;
+        let board = self.board;
+        let cells = self.cells.len();
+        // The chains of each digit as a union-find forest: each cell's
+        // parent, and whether its color differs from its parent's.
+        let mut parent: [[u16; 256]; 16] = [std::array::from_fn(|c| c as u16); 16];
+        let mut flip = [[false; 256]; 16];
+        let mut linked = [false; 16];
+        for u in 0..board.unit_count() {
+            let unit = board.cells_in_unit(u);
+            // The places of each digit in the unit, by position.
+            let mut places = [0u32; 17];
+            for (i, &p) in unit.iter().enumerate() {
+                for v in self.cells[p].iter() {
+                    places[v] |= 1 << i;
+                }
+            }
+            for v in (1..=board.side()).filter(|v| places[*v].count_ones() == 2) {
+                let (parent, flip) = (&mut parent[v - 1], &mut flip[v - 1]);
+                let a = unit[places[v].trailing_zeros() as usize];
+                let b = unit[31 - places[v].leading_zeros() as usize];
+                let (ra, fa) = find(parent, flip, a);
+                let (rb, fb) = find(parent, flip, b);
+                if ra == rb {
+                    // Along the chain, both take the digit or neither does.
+                    if fa == fb {
+                        return Err(Contradiction);
+                    }
+                    continue;
+                }
+                parent[rb] = ra as u16;
+                flip[rb] = fa == fb;
+                linked[v - 1] = true;
+            }
+        }
+        for v in (1..=board.side()).filter(|v| linked[*v - 1]) {
+            let (parent, flip) = (&mut parent[v - 1], &mut flip[v - 1]);
+            // Each cell's chain, by its root, and its color, then the cells
+            // of each chain as a list threaded through `next`, from `first`.
+            let mut root = [0; 256];
+            let mut color = [false; 256];
+            let mut first = [usize::MAX; 256];
+            let mut next = [usize::MAX; 256];
+            let mut size = [0u16; 256];
+            for c in 0..cells {
+                let (r, f) = find(parent, flip, c);
+                (root[c], color[c]) = (r, f);
+                next[c] = first[r];
+                first[r] = c;
+                size[r] += 1;
+            }
+            let mut seen = [0u8; 256];
+            for r in (0..cells).filter(|r| size[*r] > 1) {
+                // The colors of the chain each cell is a peer of, as bits,
+                // and the color two peers share, if any do, which can't be
+                // the one that takes the digit.
+                seen[..cells].fill(0);
+                let mut clash = None;
+                let mut m = first[r];
+                while m != usize::MAX {
+                    for &n in board.neighbors(m) {
+                        if root[n] == r && color[n] == color[m] {
+                            clash = Some(color[m]);
+                        }
+                        seen[n] |= 1 << color[m] as u8;
+                    }
+                    m = next[m];
+                }
+                for c in 0..cells {
+                    let ruled_out = match clash {
+                        Some(wrong) => root[c] == r && color[c] == wrong,
+                        None => root[c] != r && seen[c] == 0b11,
+                    };
+                    if ruled_out && self.cells[c].contains(v) {
+                        progress = true;
+                        if !self.eliminate_with(c, v, j) {
+                            return Err(Contradiction);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(progress)
+    }
+
+    fn naked_pairs_pass<J: Journal>(&mut self, j: &mut J) -> Result<bool, Contradiction> {
+        let mut progress = false;
+        let board = self.board;
+        for u in 0..board.unit_count() {
+            let unit = board.cells_in_unit(u);
+            for (i, &a) in unit.iter().enumerate() {
+                let pair = self.cells[a];
+                if pair.len() != 2 {
+                    continue;
+                }
+                for &b in &unit[i + 1..] {
+                    if self.cells[b] != pair {
+                        continue;
+                    }
+                    for &c in unit.iter().filter(|c| **c != a && **c != b) {
+                        for v in pair.iter() {
+                            if self.cells[c].contains(v) {
+                                progress = true;
+                                if !self.eliminate_with(c, v, j) {
+                                    return Err(Contradiction);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(progress)
+    }
+
+    fn naked_singles_pass<J: Journal>(&mut self, j: &mut J) -> Result<bool, Contradiction> {
+        let mut progress = false;
+        for k in 0..self.cells.len() {
+            match self.cells[k].len() {
+                0 => return Err(Contradiction),
+                1 => {
+                    let v = self.uniq_val(k);
+                    let board = self.board;
+                    if board
+                        .neighbors(k)
+                        .iter()
+                        .any(|n| self.cells[*n].contains(v))
+                    {
+                        progress = true;
+                        if !self.naked_single(k, j) {
+                            return Err(Contradiction);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(progress)
+    }
+
+    fn hidden_singles_pass<J: Journal>(&mut self, j: &mut J) -> Result<bool, Contradiction> {
+        let mut progress = false;
+        let board = self.board;
+        for u in 0..board.unit_count() {
+            for v in 1..=board.side() {
+                let mut ps = board
+                    .cells_in_unit(u)
+                    .iter()
+                    .filter(|p| self.cells[**p].contains(v));
+                match (ps.next(), ps.next()) {
+                    (None, _) => return Err(Contradiction),
+                    (Some(&p), None) if self.cells[p].len() > 1 => {
+                        progress = true;
+                        if !self.assign_with(p, v, j) {
+                            return Err(Contradiction);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(progress)
+    }
+}
+
+/// The root of `c` in the union-find forest of `parent`, and whether the
+/// color of `c` differs from the root's. Compresses the path on the way.
+fn find(parent: &mut [u16; 256], flip: &mut [bool; 256], c: usize) -> (usize, bool) {
+    let p = parent[c] as usize;
+    if p == c {
+        return (c, false);
+    }
+    let (root, f) = find(parent, flip, p);
+    parent[c] = root as u16;
+    flip[c] ^= f;
+    (root, flip[c])
+}
+
+/// Steps `chosen`, increasing indices below `count`, to the next choice of
+/// as many of them in lexicographic order, starting from `0, 1, 2, ...`.
+/// Returns `false`, leaving `chosen` as it was, after the last one.
+pub(super) fn next_choice(chosen: &mut [usize], count: usize) -> bool {
+    let size = chosen.len();
+    let Some(k) = (0..size).rev().find(|k| chosen[*k] < count - size + k) else {
+        return false;
+    };
+    chosen[k] += 1;
+    for next in k + 1..size {
+        chosen[next] = chosen[next - 1] + 1;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LogicalResult, Technique, Techniques};
+    use crate::board::SudokuBoard;
+    use crate::candidates::Cell;
+    use crate::error::Contradiction;
+    use crate::sudoku::Sudoku;
+
+    #[test]
+    fn naked_singles_pass() {
+        let board = SudokuBoard::new();
+        let mut sudoku = Sudoku::empty(&board);
+        // Pin cell 0 to 5 behind propagation's back.
+        (1..=9)
+            .filter(|v| *v != 5)
+            .for_each(|v| sudoku.cells[0].remove(v));
+        assert_eq!(sudoku.propagate_naked_singles(), Ok(true));
+        assert!(board
+            .neighbors(0)
+            .iter()
+            .all(|n| !sudoku.cells[*n].contains(5)));
+        assert_eq!(sudoku.propagate_naked_singles(), Ok(false));
+    }
+
+    #[test]
+    fn hidden_singles_pass() {
+        let board = SudokuBoard::new();
+        let mut sudoku = Sudoku::empty(&board);
+        // Only cell 0 of the first row is left for a 1.
+        (1..9).for_each(|k| sudoku.cells[k].remove(1));
+        assert_eq!(sudoku.propagate_hidden_singles(), Ok(true));
+        assert_eq!(sudoku.cells[0].single(), Some(1));
+        assert_eq!(sudoku.propagate_hidden_singles(), Ok(false));
+
+        (0..9).for_each(|k| sudoku.cells[k * 9 + 4].remove(2));
+        assert_eq!(sudoku.propagate_hidden_singles(), Err(Contradiction));
+    }
+
+    #[test]
+    fn naked_pairs_pass() {
+        let board = SudokuBoard::new();
+        let mut sudoku = Sudoku::empty(&board);
+        for k in [0, 1] {
+            (3..=9).for_each(|v| sudoku.cells[k].remove(v));
+        }
+        assert_eq!(sudoku.eliminate_naked_pairs(), Ok(true));
+        // Cells 0 and 1 share a row and a box, and both lose 1 and 2.
+        for k in [2, 8, 9, 20] {
+            assert!(!sudoku.cells[k].contains(1) && !sudoku.cells[k].contains(2));
+        }
+        assert!(sudoku.cells[27].contains(1));
+        assert_eq!(sudoku.eliminate_naked_pairs(), Ok(false));
+    }
+
+    #[test]
+    fn naked_pairs_cut_guesses() {
+        let board = SudokuBoard::new();
+        let s = "85...24..72......9..4.........1.7..23.5...9...4...........8..7..17..........36.4.";
+        let mut sudoku = Sudoku::from(s, &board).unwrap();
+        let with_pairs = sudoku.solve_stats().1.guesses;
+        let solution = sudoku.solve().unwrap().to_line();
+        sudoku.set_techniques(Techniques::NONE);
+        assert!(with_pairs < sudoku.solve_stats().1.guesses);
+        assert_eq!(sudoku.solve().unwrap().to_line(), solution);
+    }
+
+    #[test]
+    fn pointing_pass() {
+        let board = SudokuBoard::new();
+        let mut sudoku = Sudoku::empty(&board);
+        // In the first box, 1 is only left for the first row.
+        (9..27)
+            .filter(|k| k % 9 < 3)
+            .for_each(|k| sudoku.cells[k].remove(1));
+        assert_eq!(sudoku.eliminate_pointing(), Ok(true));
+        assert!((3..9).all(|k| !sudoku.cells[k].contains(1)));
+        assert!((0..3).all(|k| sudoku.cells[k].contains(1)));
+        assert!(sudoku.cells[27].contains(1));
+        assert_eq!(sudoku.eliminate_pointing(), Ok(false));
+    }
+
+    #[test]
+    fn claiming_pass() {
+        let board = SudokuBoard::new();
+        let mut sudoku = Sudoku::empty(&board);
+        // In the first row, 1 is only left for the first box, which pointing
+        // can't tell.
+        (3..9).for_each(|k| sudoku.cells[k].remove(1));
+        assert_eq!(sudoku.eliminate_pointing(), Ok(false));
+        assert_eq!(sudoku.eliminate_claiming(), Ok(true));
+        for k in 0..27 {
+            assert_eq!(sudoku.cells[k].contains(1), k < 3 || (k >= 9 && k % 9 >= 3));
+        }
+        assert!(sudoku.cells[27].contains(1));
+        assert_eq!(sudoku.eliminate_claiming(), Ok(false));
+    }
+
+    #[test]
+    fn pointing_cuts_guesses() {
+        let board = SudokuBoard::new();
+        let s = ".98.1....2......6.............3.2.5..84.........6.........4.8.93..5...........1..";
+        let mut sudoku = Sudoku::from(s, &board).unwrap();
+        // Hidden subsets alone would spare every guess here too.
+        let others = Techniques::all()
+            .without(Technique::HiddenPairs)
+            .without(Technique::Claiming)
+            .without(Technique::HiddenTriples)
+            .without(Technique::Coloring);
+        sudoku.set_techniques(others);
+        let with_pointing = sudoku.solve_stats().1.guesses;
+        let solution = sudoku.solve().unwrap().to_line();
+        sudoku.set_techniques(others.without(Technique::Pointing));
+        assert!(with_pointing * 10 < sudoku.solve_stats().1.guesses);
+        assert_eq!(sudoku.solve().unwrap().to_line(), solution);
+    }
+
+    #[test]
+    fn x_wing_pass() {
+        let board = SudokuBoard::new();
+        let mut sudoku = Sudoku::empty(&board);
+        // In rows 0 and 4, 5 is only left for columns 1 and 7.
+        for row in [0, 4] {
+            (0..9)
+                .filter(|c| *c != 1 && *c != 7)
+                .for_each(|c| sudoku.cells[row * 9 + c].remove(5));
+        }
+        assert_eq!(sudoku.eliminate_x_wing(), Ok(true));
+        for row in (0..9).filter(|r| *r != 0 && *r != 4) {
+            assert!(!sudoku.cells[row * 9 + 1].contains(5));
+            assert!(!sudoku.cells[row * 9 + 7].contains(5));
+            assert!(sudoku.cells[row * 9 + 2].contains(5));
+        }
+        assert!(sudoku.cells[1].contains(5) && sudoku.cells[43].contains(5));
+        assert_eq!(sudoku.eliminate_x_wing(), Ok(false));
+    }
+
+    #[test]
+    fn x_wing_spares_guesses() {
+        let board = SudokuBoard::new();
+        let s = ".5...3...19...56..6...4......64..2...8..9..64.41....83....1.7.681...63...69....18";
+        let mut sudoku = Sudoku::from(s, &board).unwrap();
+        assert_eq!(sudoku.solve_stats().1.guesses, 0);
+        let solution = sudoku.solve().unwrap().to_line();
+        // A swordfish spans the X-Wing here, so takes its place.
+        let others = Techniques::all().without(Technique::Swordfish);
+        sudoku.set_techniques(others.without(Technique::XWing));
+        assert!(sudoku.solve_stats().1.guesses > 0);
+        sudoku.set_techniques(others);
+        assert_eq!(sudoku.solve_stats().1.guesses, 0);
+        sudoku.set_techniques(Techniques::NONE);
+        assert!(sudoku.solve_stats().1.guesses > 1);
+        assert_eq!(sudoku.solve().unwrap().to_line(), solution);
+    }
+
+    #[test]
+    fn swordfish_pass() {
+        let board = SudokuBoard::new();
+        let mut sudoku = Sudoku::empty(&board);
+        // In rows 0, 3 and 6, 5 is only left for columns 1, 4 and 7, and
+        // row 3 has it in columns 1 and 4 alone.
+        for (row, kept) in [(0, [1, 4, 7]), (3, [1, 4, 4]), (6, [1, 4, 7])] {
+            (0..9)
+                .filter(|c| !kept.contains(c))
+                .for_each(|c| sudoku.cells[row * 9 + c].remove(5));
+        }
+        assert_eq!(sudoku.eliminate_x_wing(), Ok(false));
+        assert_eq!(sudoku.eliminate_swordfish(), Ok(true));
+        for row in (0..9).filter(|r| r % 3 != 0) {
+            assert!([1, 4, 7]
+                .iter()
+                .all(|c| !sudoku.cells[row * 9 + c].contains(5)));
+            assert!(sudoku.cells[row * 9 + 2].contains(5));
+        }
+        assert!(sudoku.cells[1].contains(5) && sudoku.cells[61].contains(5));
+        assert_eq!(sudoku.eliminate_swordfish(), Ok(false));
+
+        // Three rows can't share two columns for a digit.
+        let mut sudoku = Sudoku::empty(&board);
+        for row in [0, 3, 6] {
+            (2..9).for_each(|c| sudoku.cells[row * 9 + c].remove(5));
+        }
+        assert_eq!(sudoku.eliminate_swordfish(), Err(Contradiction));
+    }
+
+    #[test]
+    fn swordfish_spares_guesses() {
+        let board = SudokuBoard::new();
+        let s = ".6.5.4.3.1...9...8.........9...5...6.4.6.2.7.7...4...5.........4...8...1.5.2.3.4.";
+        let mut sudoku = Sudoku::from(s, &board).unwrap();
+        assert_eq!(sudoku.solve_stats().1.guesses, 0);
+        assert!(matches!(
+            sudoku.solve_logical_only(),
+            LogicalResult::Solved(_)
+        ));
+        let solution = sudoku.solve().unwrap().to_line();
+        sudoku.set_techniques(Techniques::all().without(Technique::Swordfish));
+        assert!(sudoku.solve_stats().1.guesses > 0);
+        assert!(matches!(
+            sudoku.solve_logical_only(),
+            LogicalResult::Stuck(_)
+        ));
+        assert_eq!(sudoku.solve().unwrap().to_line(), solution);
+    }
+
+    #[test]
+    fn claiming_spares_guesses() {
+        let board = SudokuBoard::new();
+        let s = ".476...5.8.3.....2.....9......8.5..6...1.....6.24......78...51...6....4..9...4..7";
+        let mut sudoku = Sudoku::from(s, &board).unwrap();
+        assert_eq!(sudoku.solve_stats().1.guesses, 0);
+        let solution = sudoku.solve().unwrap().to_line();
+        sudoku.set_techniques(Techniques::all().without(Technique::Claiming));
+        assert!(sudoku.solve_stats().1.guesses > 0);
+        assert_eq!(sudoku.solve().unwrap().to_line(), solution);
+    }
+
+    #[test]
+    fn hidden_pairs_pass() {
+        let board = SudokuBoard::new();
+        let mut sudoku = Sudoku::empty(&board);
+        // In the first row, 1 and 2 are only left for cells 0 and 1.
+        for k in 2..9 {
+            sudoku.cells[k].remove(1);
+            sudoku.cells[k].remove(2);
+        }
+        assert_eq!(sudoku.eliminate_hidden_subsets(2), Ok(true));
+        assert_eq!(sudoku.candidates(0), vec![1, 2]);
+        assert_eq!(sudoku.candidates(1), vec![1, 2]);
+        assert!(sudoku.cells[2].contains(3) && sudoku.cells[9].contains(3));
+        assert_eq!(sudoku.eliminate_hidden_subsets(2), Ok(false));
+    }
+
+    #[test]
+    fn hidden_triples_pass() {
+        let board = SudokuBoard::new();
+        let mut sudoku = Sudoku::empty(&board);
+        // In the first column, 1, 2 and 3 are only left for its first
+        // three cells.
+        for k in (27..81).step_by(9) {
+            (1..=3).for_each(|v| sudoku.cells[k].remove(v));
+        }
+        assert_eq!(sudoku.eliminate_hidden_subsets(2), Ok(false));
+        assert_eq!(sudoku.eliminate_hidden_subsets(3), Ok(true));
+        for k in [0, 9, 18] {
+            assert_eq!(sudoku.candidates(k), vec![1, 2, 3]);
+        }
+        assert_eq!(sudoku.eliminate_hidden_subsets(3), Ok(false));
+    }
+
+    #[test]
+    fn hidden_pairs_spare_guesses() {
+        let board = SudokuBoard::new();
+        let s = "......8.17..2........5.6......7...5..1....3...8.......5......2..4..8....6...3....";
+        let mut sudoku = Sudoku::from(s, &board).unwrap();
+        assert_eq!(sudoku.solve_stats().1.guesses, 0);
+        let solution = sudoku.solve().unwrap().to_line();
+        sudoku.set_techniques(Techniques::all().without(Technique::HiddenPairs));
+        assert!(sudoku.solve_stats().1.guesses > 0);
+        assert_eq!(sudoku.solve().unwrap().to_line(), solution);
+    }
+
+    /// An empty grid with 1 ruled out of `lines`, rows or columns, but for
+    /// the two cells listed with each.
+    fn ones_in_two_places<'a>(
+        board: &'a SudokuBoard,
+        lines: &[(&[usize], [usize; 2])],
+    ) -> Sudoku<'a> {
+        let mut sudoku = Sudoku::empty(board);
+        for (line, keep) in lines {
+            for &k in line.iter().filter(|k| !keep.contains(k)) {
+                sudoku.cells[k].remove(1);
+            }
+        }
+        sudoku
+    }
+
+    #[test]
+    fn coloring_pass() {
+        let board = SudokuBoard::new();
+        let row = |r: usize| (9 * r..9 * r + 9).collect::<Vec<_>>();
+        let col = |c: usize| (c..81).step_by(9).collect::<Vec<_>>();
+        // The chain 0, 4, 40, 36 around a rectangle: either 0 and 40 take 1
+        // or 4 and 36 do, and the rest of the first column sees both.
+        let (row0, row4, col4, col2) = (row(0), row(4), col(4), col(2));
+        let lines: [(&[usize], [usize; 2]); 3] =
+            [(&row0, [0, 4]), (&col4, [4, 40]), (&row4, [40, 36])];
+        let mut sudoku = ones_in_two_places(&board, &lines);
+        assert_eq!(sudoku.eliminate_coloring(), Ok(true));
+        for k in col(0) {
+            assert_eq!(sudoku.cells[k].contains(1), k == 0 || k == 36);
+        }
+        assert!(sudoku.cells[10].contains(1));
+        assert_eq!(sudoku.eliminate_coloring(), Ok(false));
+
+        // The chain 0, 4, 40, 38, 11 puts 0 and 11, peers in the first box,
+        // in the same color, so it's the other color that takes 1.
+        let lines: [(&[usize], [usize; 2]); 4] = [
+            (&row0, [0, 4]),
+            (&col4, [4, 40]),
+            (&row4, [40, 38]),
+            (&col2, [38, 11]),
+        ];
+        let mut sudoku = ones_in_two_places(&board, &lines);
+        assert_eq!(sudoku.eliminate_coloring(), Ok(true));
+        for k in [0, 11, 40] {
+            assert!(!sudoku.cells[k].contains(1));
+        }
+        assert!(sudoku.cells[4].contains(1) && sudoku.cells[38].contains(1));
+    }
+
+    #[test]
+    fn coloring_spares_guesses() {
+        let board = SudokuBoard::new();
+        let s = "1....6.8..64..........4...7....9.6...7.4..5..5...7.1...5....32.3....8...4........";
+        let mut sudoku = Sudoku::from(s, &board).unwrap();
+        assert_eq!(sudoku.solve_stats().1.guesses, 0);
+        assert!(sudoku.propagated().is_solved());
+        let solution = sudoku.solve().unwrap().to_line();
+        sudoku.set_techniques(Techniques::all().without(Technique::Coloring));
+        assert!(sudoku.solve_stats().1.guesses > 0);
+        assert_eq!(sudoku.solve().unwrap().to_line(), solution);
+    }
+
+    #[test]
+    fn logic_alone_solves_or_gets_stuck() {
+        let board = SudokuBoard::new();
+        let s = "1....6.8..64..........4...7....9.6...7.4..5..5...7.1...5....32.3....8...4........";
+        let sudoku = Sudoku::from(s, &board).unwrap();
+        let solution = sudoku.solve().unwrap();
+        assert!(sudoku.solve_logical_only() == LogicalResult::Solved(solution));
+
+        let mut sudoku = sudoku.clone();
+        sudoku.set_techniques(Techniques::all().without(Technique::Coloring));
+        let LogicalResult::Stuck(partial) = sudoku.solve_logical_only() else {
+            panic!("solved without coloring");
+        };
+        assert!(!partial.is_solved());
+        assert_eq!(partial.to_line(), sudoku.propagated().to_line());
+
+        // 1 is left nowhere in the first row.
+        let mut sudoku = Sudoku::empty(&board);
+        (0..9).for_each(|k| sudoku.cells[k].remove(1));
+        assert!(sudoku.solve_logical_only() == LogicalResult::Contradiction);
+    }
+
+    #[test]
+    fn parsed_grids_have_no_singles_left() {
+        let board = SudokuBoard::new();
+        let s = "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+        let mut sudoku = Sudoku::from(s, &board).unwrap();
+        assert_eq!(sudoku.propagate_naked_singles(), Ok(false));
+        assert_eq!(sudoku.propagate_hidden_singles(), Ok(false));
+    }
+}
@@ -0,0 +1,150 @@
+//! Thermo sudoku: thermometers drawn over the grid, along which the digits
+//! strictly increase from the bulb.
+
+use super::{Constraint, Sudoku};
+use crate::board::SudokuBoard;
+use crate::candidates::Cell;
+use crate::error::{Contradiction, ParseError};
+use crate::journal::Journal;
+
+/// A path of cells whose digits strictly increase along it, starting from
+/// the bulb, `cells[0]`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Thermo {
+    pub cells: Vec<usize>,
+}
+
+impl<'a> Sudoku<'a> {
+    /// Parses a puzzle like [`Sudoku::from`], with `thermos` to satisfy on
+    /// top of the board's units.
+    ///
+    /// The thermometers are enforced by the search: before each guess every
+    /// cell along one rules out the digits no greater than the smallest
+    /// left for the cell before it, and no smaller than the largest left
+    /// for the cell after it. Even on an empty grid, that leaves cell `i`
+    /// of a thermometer of `len` cells, counting from the bulb at 0, only
+    /// the digits from `i + 1` to `side - len + i + 1`.
+    ///
+    /// # Panics
+    ///
+    /// If some thermometer has a cell that isn't on the board.
+    pub fn with_thermos(
+        s: &str,
+        board: &'a SudokuBoard,
+        thermos: Vec<Thermo>,
+    ) -> Result<Sudoku<'a>, ParseError> {
+        Sudoku::with_constraints(
+            s,
+            board,
+            thermos.into_iter().map(Constraint::Thermo).collect(),
+        )
+    }
+}
+
+impl<'a, C: Cell> Sudoku<'a, C> {
+    /// The thermometers this grid must satisfy, none for a plain sudoku.
+    pub fn thermos(&self) -> impl Iterator<Item = &Thermo> {
+        self.constraints().iter().filter_map(|c| match c {
+            Constraint::Thermo(thermo) => Some(thermo),
+            _ => None,
+        })
+    }
+
+    pub(super) fn thermo_pass<J: Journal>(
+        &mut self,
+        cells: &[usize],
+        j: &mut J,
+    ) -> Result<bool, Contradiction> {
+        let mut progress = false;
+        // Up from the bulb, each cell above the smallest digit before it.
+        for w in cells.windows(2) {
+            let floor = self.cells[w[0]].iter().next().ok_or(Contradiction)?;
+            for v in self.cells[w[1]].iter().filter(|v| *v <= floor) {
+                progress = true;
+                if !self.eliminate_with(w[1], v, j) {
+                    return Err(Contradiction);
+                }
+            }
+        }
+        // Down from the tip, each cell below the largest digit after it.
+        for w in cells.windows(2).rev() {
+            let ceiling = self.cells[w[1]].iter().last().ok_or(Contradiction)?;
+            for v in self.cells[w[0]].iter().filter(|v| *v >= ceiling) {
+                progress = true;
+                if !self.eliminate_with(w[0], v, j) {
+                    return Err(Contradiction);
+                }
+            }
+        }
+        Ok(progress)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Thermo;
+    use crate::board::SudokuBoard;
+    use crate::error::Contradiction;
+    use crate::sudoku::Sudoku;
+
+    fn thermos(paths: &[&[usize]]) -> Vec<Thermo> {
+        paths
+            .iter()
+            .map(|cells| Thermo {
+                cells: cells.to_vec(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn thermos_bound_candidates() {
+        let board = SudokuBoard::new();
+        // Down the first column, then along the last row.
+        let path: Vec<usize> = [0, 9, 18, 27].into_iter().chain([76, 77, 78]).collect();
+        let empty = ".".repeat(81);
+        let mut sudoku = Sudoku::with_thermos(&empty, &board, thermos(&[&path])).unwrap();
+        assert_eq!(sudoku.reduce(&mut ()), Ok(()));
+        assert_eq!(sudoku.candidates(0), vec![1, 2, 3]);
+        assert_eq!(sudoku.candidates(27), vec![4, 5, 6]);
+        assert_eq!(sudoku.candidates(78), vec![7, 8, 9]);
+
+        // No room for 9 cells past a 2 in the bulb.
+        let mut puzzle = empty.clone();
+        puzzle.replace_range(0..1, "2");
+        let path: Vec<usize> = (0..9).collect();
+        let mut sudoku = Sudoku::with_thermos(&puzzle, &board, thermos(&[&path])).unwrap();
+        assert_eq!(sudoku.reduce(&mut ()), Err(Contradiction));
+    }
+
+    #[test]
+    fn solves_thermo_puzzle() {
+        // Increasing runs of the solution, drawn as thermometers, together
+        // with 15 givens the puzzle would be ambiguous without.
+        let board = SudokuBoard::new();
+        let paths: [&[usize]; 11] = [
+            &[3, 4, 5],
+            &[12, 13, 14, 15],
+            &[33, 34, 35],
+            &[38, 39, 40],
+            &[45, 46, 47, 48],
+            &[49, 50, 51],
+            &[54, 55, 56],
+            &[59, 60, 61],
+            &[68, 69, 70],
+            &[0, 9, 18],
+            &[1, 10, 19],
+        ];
+        let givens =
+            "4..........2.....7....24..........6...........4....75....6...7.5.....6.....8....3";
+        assert_eq!(Sudoku::from(givens, &board).unwrap().count_solutions(2), 2);
+        let sudoku = Sudoku::with_thermos(givens, &board, thermos(&paths)).unwrap();
+        assert_eq!(sudoku.thermos().count(), 11);
+        assert_eq!(sudoku.count_solutions(2), 1);
+        let solved = sudoku.solve().unwrap();
+        assert_eq!(
+            solved.to_line(),
+            "417369825632158947958724316825437169791586432346912758289643571573291684164875293"
+        );
+        assert!(solved.is_valid_solution());
+    }
+}
@@ -0,0 +1,95 @@
+use crate::grid::Grid;
+
+/// A symmetry the positions of a puzzle's givens can have, whatever the
+/// digits in them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SymmetryKind {
+    /// No symmetry below holds.
+    None,
+    /// The pattern looks the same turned half a turn around the center.
+    Rotational180,
+    /// The pattern mirrors itself across the diagonal from the top left to
+    /// the bottom right.
+    Diagonal,
+    /// The top half mirrors the bottom half.
+    Horizontal,
+    /// The left half mirrors the right half.
+    Vertical,
+}
+
+impl SymmetryKind {
+    /// The cell `k` of a grid `side` cells across maps to under this
+    /// symmetry: `k` itself when it lies on the axis or at the center, and
+    /// always for [`SymmetryKind::None`].
+    pub(crate) fn mirror(self, side: usize, k: usize) -> usize {
+        let (r, c) = (k / side, k % side);
+        let last = side - 1;
+        let (r, c) = match self {
+            SymmetryKind::None => (r, c),
+            SymmetryKind::Rotational180 => (last - r, last - c),
+            SymmetryKind::Diagonal => (c, r),
+            SymmetryKind::Horizontal => (last - r, c),
+            SymmetryKind::Vertical => (r, last - c),
+        };
+        r * side + c
+    }
+}
+
+impl Grid {
+    /// The symmetry of the cells holding givens, as opposed to the empty
+    /// ones. When the pattern has several, the first in the order of
+    /// [`SymmetryKind`] wins, so the most common one in published puzzles,
+    /// rotational, comes first.
+    ///
+    /// # Panics
+    ///
+    /// Unless the grid is square.
+    pub fn clue_symmetry(&self) -> SymmetryKind {
+        let digits = self.digits();
+        let side = (digits.len() as f64).sqrt() as usize;
+        assert_eq!(side * side, digits.len(), "the grid must be square");
+        [
+            SymmetryKind::Rotational180,
+            SymmetryKind::Diagonal,
+            SymmetryKind::Horizontal,
+            SymmetryKind::Vertical,
+        ]
+        .into_iter()
+        .find(|s| (0..digits.len()).all(|k| (digits[k] != 0) == (digits[s.mirror(side, k)] != 0)))
+        .unwrap_or(SymmetryKind::None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SymmetryKind;
+    use crate::board::SudokuBoard;
+    use crate::grid::Grid;
+
+    fn symmetry(s: &str) -> SymmetryKind {
+        let board = SudokuBoard::with_box_size((s.len() as f64).sqrt().sqrt() as usize);
+        Grid::parse(s, &board).unwrap().clue_symmetry()
+    }
+
+    #[test]
+    fn clue_patterns() {
+        // Norvig's first example, symmetric under a half turn.
+        assert_eq!(
+            symmetry(
+                "..3.2.6..9..3.5..1..18.64....81.29..7.......8..67.82....26.95..8..2.3..9..5.1.3.."
+            ),
+            SymmetryKind::Rotational180
+        );
+        assert_eq!(
+            symmetry(
+                "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......"
+            ),
+            SymmetryKind::None
+        );
+        // Smaller grids work the same way.
+        assert_eq!(symmetry(".2..3..........."), SymmetryKind::Diagonal);
+        assert_eq!(symmetry("12..........34.."), SymmetryKind::Horizontal);
+        assert_eq!(symmetry("1..2............"), SymmetryKind::Vertical);
+        assert_eq!(symmetry("1..............4"), SymmetryKind::Rotational180);
+    }
+}
@@ -0,0 +1,59 @@
+use crate::journal::Journal;
+
+/// One move of the solver, as recorded by
+/// [`Sudoku::solve_traced`](crate::Sudoku::solve_traced).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    /// Propagation narrowed `cell` down to `value`.
+    Assign { cell: usize, value: usize },
+    /// Propagation ruled `value` out for `cell`.
+    Eliminate { cell: usize, value: usize },
+    /// The search tried `value` for `cell`.
+    Guess { cell: usize, value: usize },
+    /// The guess of `value` for `cell` failed: every step since the matching
+    /// [`Step::Guess`] has been undone.
+    Backtrack { cell: usize, value: usize },
+}
+
+impl Journal for Vec<Step> {
+    fn removed(&mut self, cell: usize, value: usize) {
+        self.push(Step::Eliminate { cell, value });
+    }
+
+    fn assigned(&mut self, cell: usize, value: usize) {
+        self.push(Step::Assign { cell, value });
+    }
+
+    fn guessed(&mut self, cell: usize, value: usize) {
+        self.push(Step::Guess { cell, value });
+    }
+
+    fn backtracked(&mut self, cell: usize, value: usize) {
+        self.push(Step::Backtrack { cell, value });
+    }
+}
+
+/// The placements along the current branch of the search, as recorded by
+/// [`Sudoku::solution_order`](crate::Sudoku::solution_order): those of a
+/// failed guess are dropped when it's undone.
+#[derive(Debug, Default)]
+pub(crate) struct Placements {
+    pub(crate) order: Vec<(usize, usize)>,
+    /// How many placements there were at each open guess.
+    guesses: Vec<usize>,
+}
+
+impl Journal for Placements {
+    fn assigned(&mut self, cell: usize, value: usize) {
+        self.order.push((cell, value));
+    }
+
+    fn guessed(&mut self, _: usize, _: usize) {
+        self.guesses.push(self.order.len());
+    }
+
+    fn backtracked(&mut self, _: usize, _: usize) {
+        let at = self.guesses.pop().unwrap();
+        self.order.truncate(at);
+    }
+}
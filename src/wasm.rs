@@ -0,0 +1,32 @@
+use crate::board::SudokuBoard;
+use crate::sudoku::Sudoku;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// Solves a puzzle on the standard board, returning the solution as a line
+/// of 81 digits, or `None` (`null` on the JavaScript side) if the puzzle is
+/// malformed or has no solution.
+#[wasm_bindgen]
+pub fn solve_line(input: &str) -> Option<String> {
+    let solved = Sudoku::from(input, SudokuBoard::standard()).ok()?.solve()?;
+    Some(solved.to_line())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::solve_line;
+
+    #[test]
+    fn solves_lines() {
+        let s = "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+        assert_eq!(
+            solve_line(s).as_deref(),
+            Some(
+                "417369825632158947958724316825437169791586432346912758289643571573291684164875293"
+            )
+        );
+        assert_eq!(solve_line("1234"), None);
+        let no_solution =
+            "49....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+        assert_eq!(solve_line(no_solution), None);
+    }
+}